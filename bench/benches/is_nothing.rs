@@ -0,0 +1,83 @@
+use std::{fmt, hint::black_box};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use serde::{Serialize, Serializer};
+use serde_nothing::IsNothing;
+
+/// Value with custom `Serialize` that formats through `collect_str`.
+#[derive(Clone, Copy)]
+struct Id(u64);
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0 == 0 {
+            Ok(())
+        } else {
+            write!(f, "id-{:016x}", self.0)
+        }
+    }
+}
+
+impl Serialize for Id {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl IsNothing for Id {
+    fn is_nothing(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+#[derive(Clone, serde_nothing_derive::IsNothing, serde_derive::Serialize)]
+struct Record {
+    id: Id,
+    name: String,
+    score: f64,
+    tags: Vec<String>,
+    parent: Option<Box<Record>>,
+    counters: [u32; 4],
+}
+
+fn records() -> Vec<Record> {
+    (0..1024u64)
+        .map(|i| Record {
+            id: Id(0),
+            name: String::new(),
+            score: 0.0,
+            tags: Vec::new(),
+            parent: None,
+            counters: [0, 0, 0, (i % 2) as u32],
+        })
+        .collect()
+}
+
+fn bench_is_nothing(c: &mut Criterion) {
+    let records = records();
+
+    let mut group = c.benchmark_group("is_nothing");
+
+    group.bench_function("serde", |b| {
+        b.iter(|| {
+            black_box(&records)
+                .iter()
+                .filter(|r| serde_nothing::is_nothing(*r))
+                .count()
+        })
+    });
+
+    group.bench_function("derive", |b| {
+        b.iter(|| {
+            black_box(&records)
+                .iter()
+                .filter(|r| IsNothing::is_nothing(*r))
+                .count()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_is_nothing);
+criterion_main!(benches);
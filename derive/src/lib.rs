@@ -0,0 +1,300 @@
+//!
+//! Derive macro for `serde_nothing::IsNothing` trait.
+//!
+//! Generated implementation checks fields one by one
+//! following the same rules `serde_nothing::Nothing` serializer applies
+//! to the output of `#[derive(Serialize)]`.
+//! `#[serde(...)]` attributes that affect serialization are taken into account.
+//! Once any rule is registered in `serde_nothing::registry`,
+//! values of `Serialize` types are checked with `serde_nothing::is_nothing` instead.
+//!
+
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_macro_input, parse_quote, punctuated::Punctuated, Attribute, Data, DeriveInput, Expr,
+    ExprLit, Fields, Lit, Meta, Path, Token, Type, WherePredicate,
+};
+
+/// Derives `serde_nothing::IsNothing` for a struct or enum.
+///
+/// Fields are checked with their own `IsNothing` implementation when one exists
+/// and with `serde_nothing::is_nothing` otherwise.
+/// Fields with types that mention type parameters require `IsNothing` implementation.
+#[proc_macro_derive(IsNothing, attributes(serde))]
+pub fn derive_is_nothing(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match is_nothing_impl(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    skip: bool,
+    flatten: bool,
+    skip_if: Option<Path>,
+    serialize_with: Option<Path>,
+}
+
+#[derive(Default)]
+struct ContainerAttrs {
+    /// Serialized representation differs from the shape of the type.
+    fallback: bool,
+}
+
+fn serde_metas(attrs: &[Attribute]) -> syn::Result<Vec<Meta>> {
+    let mut metas = Vec::new();
+    for attr in attrs {
+        if attr.path().is_ident("serde") {
+            let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+            metas.extend(nested);
+        }
+    }
+    Ok(metas)
+}
+
+fn str_path(meta: &Meta) -> syn::Result<Path> {
+    if let Meta::NameValue(nv) = meta {
+        if let Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) = &nv.value
+        {
+            return lit.parse();
+        }
+    }
+    Err(syn::Error::new_spanned(meta, "expected `name = \"path\"`"))
+}
+
+fn container_attrs(attrs: &[Attribute]) -> syn::Result<ContainerAttrs> {
+    let mut result = ContainerAttrs::default();
+    for meta in serde_metas(attrs)? {
+        let path = meta.path();
+        if path.is_ident("into")
+            || path.is_ident("tag")
+            || path.is_ident("content")
+            || path.is_ident("untagged")
+        {
+            result.fallback = true;
+        }
+    }
+    Ok(result)
+}
+
+fn field_attrs(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
+    let mut result = FieldAttrs::default();
+    for meta in serde_metas(attrs)? {
+        let path = meta.path();
+        if path.is_ident("skip") || path.is_ident("skip_serializing") {
+            result.skip = true;
+        } else if path.is_ident("flatten") {
+            result.flatten = true;
+        } else if path.is_ident("skip_serializing_if") {
+            result.skip_if = Some(str_path(&meta)?);
+        } else if path.is_ident("serialize_with") {
+            result.serialize_with = Some(str_path(&meta)?);
+        } else if path.is_ident("with") {
+            let mut with = str_path(&meta)?;
+            with.segments.push(parse_quote!(serialize));
+            result.serialize_with = Some(with);
+        }
+    }
+    Ok(result)
+}
+
+/// Returns true if the type mentions any of the type parameters.
+fn mentions(tokens: TokenStream, params: &[Ident]) -> bool {
+    tokens.into_iter().any(|tree| match tree {
+        TokenTree::Ident(ident) => params.contains(&ident),
+        TokenTree::Group(group) => mentions(group.stream(), params),
+        _ => false,
+    })
+}
+
+/// Returns expression checking single field bound to `binding` by reference.
+///
+/// Autoref dispatch cannot see implementations that depend on type parameters,
+/// so generic fields use `IsNothing` directly and get a bound instead.
+fn field_check(
+    attrs: &FieldAttrs,
+    ty: &Type,
+    binding: &TokenStream,
+    params: &[Ident],
+    bounds: &mut Vec<WherePredicate>,
+) -> TokenStream {
+    let check = match &attrs.serialize_with {
+        Some(with) => quote! {
+            #with(#binding, ::serde_nothing::Nothing).is_ok()
+        },
+        None if mentions(ty.to_token_stream(), params) => {
+            bounds.push(parse_quote!(#ty: ::serde_nothing::IsNothing));
+            quote! {
+                <#ty as ::serde_nothing::IsNothing>::is_nothing(#binding)
+            }
+        }
+        None => quote! {
+            (&::serde_nothing::__private::Probe(#binding)).__is_nothing()
+        },
+    };
+
+    match &attrs.skip_if {
+        Some(skip_if) => quote! { (#skip_if(#binding) || #check) },
+        None => check,
+    }
+}
+
+/// Returns pattern binding all fields and expression checking them.
+fn fields_check(
+    fields: &Fields,
+    params: &[Ident],
+    bounds: &mut Vec<WherePredicate>,
+) -> syn::Result<(TokenStream, TokenStream, bool)> {
+    let mut fallback = false;
+    let mut checks = Vec::new();
+    let mut bindings = Vec::new();
+
+    for (index, field) in fields.iter().enumerate() {
+        let attrs = field_attrs(&field.attrs)?;
+        fallback |= attrs.flatten;
+
+        let binding = format_ident!("__field{}", index);
+        match &field.ident {
+            Some(ident) => bindings.push(quote!(#ident: #binding)),
+            None => bindings.push(quote!(#binding)),
+        }
+
+        if !attrs.skip {
+            checks.push(field_check(
+                &attrs,
+                &field.ty,
+                &quote!(#binding),
+                params,
+                bounds,
+            ));
+        }
+    }
+
+    let pattern = match fields {
+        Fields::Named(_) => quote!({ #(#bindings,)* }),
+        Fields::Unnamed(_) => quote!(( #(#bindings,)* )),
+        Fields::Unit => quote!(),
+    };
+
+    let check = if checks.is_empty() {
+        quote!(true)
+    } else {
+        quote!(#(#checks)&&*)
+    };
+
+    Ok((pattern, check, fallback))
+}
+
+fn is_nothing_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let container = container_attrs(&input.attrs)?;
+    let mut fallback = container.fallback;
+    let params: Vec<Ident> = input
+        .generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let mut bounds = Vec::new();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, check, flatten) = fields_check(&data.fields, &params, &mut bounds)?;
+            fallback |= flatten;
+            quote! {
+                let #ident #pattern = self;
+                #check
+            }
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let variant_ident = &variant.ident;
+                let (pattern, check, flatten) =
+                    fields_check(&variant.fields, &params, &mut bounds)?;
+                fallback |= flatten;
+
+                let mut skip = false;
+                let mut serialize_with = false;
+                for meta in serde_metas(&variant.attrs)? {
+                    let path = meta.path();
+                    if path.is_ident("skip") || path.is_ident("skip_serializing") {
+                        skip = true;
+                    } else if path.is_ident("serialize_with") || path.is_ident("with") {
+                        serialize_with = true;
+                    }
+                }
+
+                // Serializing skipped variant is an error.
+                let check = if skip { quote!(false) } else { check };
+                fallback |= serialize_with;
+
+                arms.push(quote! {
+                    #ident::#variant_ident #pattern => #check,
+                });
+            }
+
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "IsNothing cannot be derived for unions",
+            ))
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    {
+        let where_clause = generics.make_where_clause();
+        if fallback {
+            for param in &params {
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#param: ::serde_nothing::__private::Serialize));
+            }
+            where_clause
+                .predicates
+                .push(parse_quote!(Self: ::serde_nothing::__private::Serialize));
+        } else {
+            where_clause.predicates.extend(bounds);
+        }
+    }
+
+    // Registered rules are applied by `is_nothing` function only.
+    let body = if fallback {
+        quote!(::serde_nothing::is_nothing(self))
+    } else {
+        quote! {
+            #[allow(unused_imports)]
+            use ::serde_nothing::__private::{
+                ViaFields as _, ViaIsNothing as _, ViaRegistry as _, ViaSerde as _,
+            };
+            if let ::core::option::Option::Some(nothing) =
+                (&::serde_nothing::__private::Probe(self)).__registered()
+            {
+                return nothing;
+            }
+            #body
+        }
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::serde_nothing::IsNothing for #ident #ty_generics #where_clause {
+            #[inline]
+            fn is_nothing(&self) -> bool {
+                #body
+            }
+        }
+    })
+}
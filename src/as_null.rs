@@ -0,0 +1,96 @@
+//!
+//! Nothing values as explicit `null`.
+//!
+//! Use with `#[serde(with = "serde_nothing::as_null")]`
+//! to write nothing values as `null` instead of skipping them
+//! and read `null` back as nothing value.
+//! Or use `serialize` alone with `#[serde(serialize_with = "serde_nothing::as_null::serialize")]`.
+//!
+//! Values are written as options, so non-self-describing formats
+//! stay symmetric too.
+//!
+//! ```
+//! #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+//! #[serde(bound(
+//!     serialize = "T: serde::Serialize",
+//!     deserialize = "T: serde::Deserialize<'de>"
+//! ))]
+//! struct Request<T> {
+//!     #[serde(with = "serde_nothing::as_null")]
+//!     limit: T,
+//! }
+//!
+//! let json = serde_json::to_string(&Request { limit: 0u32 }).unwrap();
+//! assert_eq!(json, r#"{"limit":null}"#);
+//!
+//! let json = serde_json::to_string(&Request { limit: 5u32 }).unwrap();
+//! assert_eq!(json, r#"{"limit":5}"#);
+//!
+//! let request: Request<u32> = serde_json::from_str(r#"{"limit":null}"#).unwrap();
+//! assert_eq!(request, Request { limit: 0 });
+//! ```
+//!
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::is_nothing;
+
+/// Serializes nothing value as none and any other value as some.
+#[inline]
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + Serialize,
+    S: Serializer,
+{
+    if is_nothing(value) {
+        serializer.serialize_none()
+    } else {
+        serializer.serialize_some(value)
+    }
+}
+
+/// Deserializes none as nothing value and some as the value.
+///
+/// Same as [`null_as_nothing`](crate::lenient::null_as_nothing).
+#[inline]
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    crate::lenient::null_as_nothing(deserializer)
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_as_null() {
+    use std::{string::String, vec::Vec};
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    struct Record {
+        #[serde(with = "self")]
+        name: String,
+        #[serde(with = "self")]
+        tags: Vec<u32>,
+        #[serde(with = "self")]
+        parent: Option<u32>,
+    }
+
+    let record = Record {
+        name: String::new(),
+        tags: Vec::new(),
+        parent: None,
+    };
+    let json = serde_json::to_string(&record).unwrap();
+    assert_eq!(json, r#"{"name":null,"tags":null,"parent":null}"#);
+    assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+
+    let record = Record {
+        name: "a".into(),
+        tags: vec![0],
+        parent: Some(0),
+    };
+    let json = serde_json::to_string(&record).unwrap();
+    assert_eq!(json, r#"{"name":"a","tags":[0],"parent":0}"#);
+    assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+}
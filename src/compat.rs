@@ -0,0 +1,487 @@
+//!
+//! Compatibility check between two versions of a type.
+//!
+//! Documents written by the old version skip nothing values,
+//! so the new version must be able to fill every value that is missing in them.
+//! [`check_compat`] compares shapes of both versions, see [`describe`](crate::shape::describe),
+//! and lists changes that break reading old documents.
+//!
+
+use std::{fmt, string::String, vec::Vec};
+
+use serde::Deserialize;
+
+use crate::shape::{describe, Field, Kind, Payload, Shape, Variant};
+
+/// Result of [`check_compat`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompatReport {
+    /// Found incompatibilities.
+    pub issues: Vec<Issue>,
+}
+
+impl CompatReport {
+    /// Returns `true` if no incompatibilities were found.
+    pub fn is_compatible(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for CompatReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.issues.is_empty() {
+            return f.write_str("compatible");
+        }
+        for (index, issue) in self.issues.iter().enumerate() {
+            if index > 0 {
+                f.write_str("\n")?;
+            }
+            fmt::Display::fmt(issue, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Incompatible change at some path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Issue {
+    /// Path to the changed part, like `.field[]::Variant.inner`.
+    /// Empty for the type itself.
+    pub path: String,
+
+    /// What changed at the path.
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            fmt::Display::fmt(&self.kind, f)
+        } else {
+            write!(f, "`{}`: {}", self.path, self.kind)
+        }
+    }
+}
+
+/// Kind of incompatible change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IssueKind {
+    /// Field added in the new version cannot be missing,
+    /// it has neither `#[serde(default)]` nor optional type.
+    /// Old documents do not have it.
+    BecameRequired,
+
+    /// Field added in the new version has no nothing value.
+    /// Readers that fill missing fields from `Nothing`,
+    /// like [`from_value`](crate::value::from_value), fail on old documents.
+    NoNothingValue,
+
+    /// Part could be nothing in the old version but cannot be in the new one.
+    /// Old documents may have skipped it.
+    LostNothing,
+
+    /// Variant at index 0 changed.
+    /// Nothing value of the enum is its first variant,
+    /// skipped values of the old version would be filled with another variant.
+    FirstVariantChanged {
+        /// First variant of the old version.
+        old: &'static str,
+        /// First variant of the new version.
+        new: &'static str,
+    },
+
+    /// Variant of the old version is missing in the new one.
+    VariantRemoved {
+        /// Name of the removed variant.
+        name: &'static str,
+    },
+
+    /// Part changed its kind.
+    KindChanged {
+        /// Kind in the old version.
+        old: &'static str,
+        /// Kind in the new version.
+        new: &'static str,
+    },
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IssueKind::BecameRequired => f.write_str("new field is required"),
+            IssueKind::NoNothingValue => f.write_str("new field cannot be nothing"),
+            IssueKind::LostNothing => f.write_str("cannot be nothing anymore"),
+            IssueKind::FirstVariantChanged { old, new } => write!(
+                f,
+                "first variant changed from `{}` to `{}`, nothing value changed",
+                old, new
+            ),
+            IssueKind::VariantRemoved { name } => write!(f, "variant `{}` removed", name),
+            IssueKind::KindChanged { old, new } => {
+                write!(f, "kind changed from {} to {}", old, new)
+            }
+        }
+    }
+}
+
+/// Checks that `New` can read every document written by `Old`.
+///
+/// Both types are inspected with `Nothing` deserializer,
+/// so only changes visible to their `Deserialize` implementations are found.
+/// Removed fields are not reported since `serde` ignores unknown fields
+/// unless `deny_unknown_fields` is used.
+///
+/// ```
+/// # use serde_nothing::compat::{check_compat, IssueKind};
+/// #[derive(serde_derive::Deserialize)]
+/// struct V1 {
+///     name: String,
+/// }
+///
+/// #[derive(serde_derive::Deserialize)]
+/// struct V2 {
+///     name: String,
+///     #[serde(default)]
+///     tags: Vec<String>,
+///     port: u16,
+/// }
+///
+/// let report = check_compat::<V1, V2>();
+/// assert_eq!(report.issues.len(), 1);
+/// assert_eq!(report.issues[0].path, ".port");
+/// assert_eq!(report.issues[0].kind, IssueKind::BecameRequired);
+/// ```
+pub fn check_compat<'de, Old, New>() -> CompatReport
+where
+    Old: Deserialize<'de>,
+    New: Deserialize<'de>,
+{
+    let old = describe::<Old>();
+    let new = describe::<New>();
+
+    let mut report = CompatReport::default();
+    compare(&old, &new, &mut String::new(), &mut report.issues);
+    report
+}
+
+fn compare(old: &Shape, new: &Shape, path: &mut String, issues: &mut Vec<Issue>) {
+    let before = issues.len();
+    compare_kinds(old, new, path, issues);
+
+    // Report only the deepest part that lost its nothing value.
+    let reported = issues[before..].iter().any(|issue| {
+        issue.kind == IssueKind::LostNothing || issue.kind == IssueKind::NoNothingValue
+    });
+    if old.nothing && !new.nothing && !reported {
+        push(issues, path, IssueKind::LostNothing);
+    }
+}
+
+fn compare_kinds(old: &Shape, new: &Shape, path: &mut String, issues: &mut Vec<Issue>) {
+    match (&old.kind, &new.kind) {
+        (Kind::Unknown, _)
+        | (_, Kind::Unknown)
+        | (Kind::Any, _)
+        | (_, Kind::Any)
+        | (Kind::Recursive { .. }, _)
+        | (_, Kind::Recursive { .. }) => {}
+
+        (Kind::Option(old), Kind::Option(new)) | (Kind::Seq(old), Kind::Seq(new)) => {
+            let len = path.len();
+            if let Kind::Seq(_) = new.kind {
+                path.push_str("[]");
+            }
+            compare(old, new, path, issues);
+            path.truncate(len);
+        }
+
+        // Value that became optional is still readable.
+        (_, Kind::Option(new)) => compare_kinds(old, new, path, issues),
+
+        (
+            Kind::Map {
+                key: old_key,
+                value: old_value,
+            },
+            Kind::Map {
+                key: new_key,
+                value: new_value,
+            },
+        ) => {
+            compare_kinds(old_key, new_key, path, issues);
+            let len = path.len();
+            path.push_str("{}");
+            compare(old_value, new_value, path, issues);
+            path.truncate(len);
+        }
+
+        (Kind::Tuple(old), Kind::Tuple(new))
+        | (Kind::TupleStruct { fields: old, .. }, Kind::TupleStruct { fields: new, .. }) => {
+            compare_tuples(old, new, path, issues)
+        }
+
+        (Kind::NewtypeStruct { inner: old, .. }, Kind::NewtypeStruct { inner: new, .. }) => {
+            compare(old, new, path, issues)
+        }
+
+        (Kind::Struct { fields: old, .. }, Kind::Struct { fields: new, .. }) => {
+            compare_fields(old, new, path, issues)
+        }
+
+        (Kind::Enum { variants: old, .. }, Kind::Enum { variants: new, .. }) => {
+            compare_variants(old, new, path, issues)
+        }
+
+        (old, new) => {
+            let (old, new) = (kind_name(old), kind_name(new));
+            if old != new {
+                push(issues, path, IssueKind::KindChanged { old, new });
+            }
+        }
+    }
+}
+
+fn compare_tuples(old: &[Shape], new: &[Shape], path: &mut String, issues: &mut Vec<Issue>) {
+    if old.len() != new.len() {
+        push(
+            issues,
+            path,
+            IssueKind::KindChanged {
+                old: "tuple",
+                new: "tuple of different length",
+            },
+        );
+        return;
+    }
+
+    for (index, (old, new)) in old.iter().zip(new).enumerate() {
+        let len = path.len();
+        path.push_str(&format!("[{}]", index));
+        compare(old, new, path, issues);
+        path.truncate(len);
+    }
+}
+
+fn compare_fields(old: &[Field], new: &[Field], path: &mut String, issues: &mut Vec<Issue>) {
+    for field in new {
+        let len = path.len();
+        path.push('.');
+        path.push_str(field.name);
+
+        match old.iter().find(|old| old.name == field.name) {
+            Some(old) => compare(&old.shape, &field.shape, path, issues),
+            None => {
+                if !field.omittable {
+                    push(issues, path, IssueKind::BecameRequired);
+                }
+                if !field.shape.nothing {
+                    push(issues, path, IssueKind::NoNothingValue);
+                }
+            }
+        }
+
+        path.truncate(len);
+    }
+}
+
+fn compare_variants(old: &[Variant], new: &[Variant], path: &mut String, issues: &mut Vec<Issue>) {
+    if let (Some(old), Some(new)) = (old.first(), new.first()) {
+        if old.name != new.name {
+            push(
+                issues,
+                path,
+                IssueKind::FirstVariantChanged {
+                    old: old.name,
+                    new: new.name,
+                },
+            );
+        }
+    }
+
+    for variant in old {
+        let new = match new.iter().find(|new| new.name == variant.name) {
+            None => {
+                push(
+                    issues,
+                    path,
+                    IssueKind::VariantRemoved { name: variant.name },
+                );
+                continue;
+            }
+            Some(new) => new,
+        };
+
+        let len = path.len();
+        path.push_str("::");
+        path.push_str(variant.name);
+
+        match (&variant.payload, &new.payload) {
+            (Payload::Unknown, _) | (_, Payload::Unknown) | (Payload::Unit, Payload::Unit) => {}
+            (Payload::Newtype(old), Payload::Newtype(new)) => compare(old, new, path, issues),
+            (Payload::Tuple(old), Payload::Tuple(new)) => compare_tuples(old, new, path, issues),
+            (Payload::Struct(old), Payload::Struct(new)) => compare_fields(old, new, path, issues),
+            (old, new) => push(
+                issues,
+                path,
+                IssueKind::KindChanged {
+                    old: payload_name(old),
+                    new: payload_name(new),
+                },
+            ),
+        }
+
+        path.truncate(len);
+    }
+}
+
+fn push(issues: &mut Vec<Issue>, path: &str, kind: IssueKind) {
+    issues.push(Issue {
+        path: path.into(),
+        kind,
+    });
+}
+
+fn kind_name(kind: &Kind) -> &'static str {
+    match kind {
+        Kind::Bool => "bool",
+        Kind::I8
+        | Kind::I16
+        | Kind::I32
+        | Kind::I64
+        | Kind::I128
+        | Kind::U8
+        | Kind::U16
+        | Kind::U32
+        | Kind::U64
+        | Kind::U128 => "integer",
+        Kind::F32 | Kind::F64 => "float",
+        Kind::Char => "char",
+        Kind::Str => "string",
+        Kind::Bytes => "bytes",
+        Kind::Unit | Kind::UnitStruct { .. } => "unit",
+        Kind::Option(_) => "option",
+        Kind::Seq(_) => "sequence",
+        Kind::Tuple(_) | Kind::TupleStruct { .. } => "tuple",
+        Kind::Map { .. } => "map",
+        Kind::NewtypeStruct { .. } => "newtype",
+        Kind::Struct { .. } => "struct",
+        Kind::Enum { .. } => "enum",
+        Kind::Identifier => "identifier",
+        Kind::Any => "any",
+        Kind::Recursive { .. } => "recursive",
+        Kind::Unknown => "unknown",
+    }
+}
+
+fn payload_name(payload: &Payload) -> &'static str {
+    match payload {
+        Payload::Unit => "unit variant",
+        Payload::Newtype(_) => "newtype variant",
+        Payload::Tuple(_) => "tuple variant",
+        Payload::Struct(_) => "struct variant",
+        Payload::Unknown => "unknown variant",
+    }
+}
+
+#[test]
+fn test_compat() {
+    use std::{collections::BTreeMap, num::NonZeroU32};
+
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    enum ModeV1 {
+        Off,
+        On(u32),
+        Legacy,
+    }
+
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    enum ModeV2 {
+        On(u32),
+        Off,
+    }
+
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct InnerV1 {
+        limit: u32,
+    }
+
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct InnerV2 {
+        limit: NonZeroU32,
+    }
+
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct ConfigV1 {
+        name: String,
+        mode: ModeV1,
+        inner: BTreeMap<String, InnerV1>,
+        count: u8,
+    }
+
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct ConfigV2 {
+        name: Option<String>,
+        mode: ModeV2,
+        inner: BTreeMap<String, InnerV2>,
+        count: u64,
+        #[serde(default)]
+        added: Vec<u32>,
+        required: NonZeroU32,
+        port: u16,
+        #[serde(default = "threads")]
+        threads: NonZeroU32,
+    }
+
+    fn threads() -> NonZeroU32 {
+        NonZeroU32::new(4).unwrap()
+    }
+
+    let report = check_compat::<ConfigV1, ConfigV1>();
+    assert!(report.is_compatible(), "{}", report);
+
+    let report = check_compat::<ConfigV1, ConfigV2>();
+    assert_eq!(
+        report.issues,
+        [
+            Issue {
+                path: ".mode".into(),
+                kind: IssueKind::FirstVariantChanged {
+                    old: "Off",
+                    new: "On"
+                },
+            },
+            Issue {
+                path: ".mode".into(),
+                kind: IssueKind::VariantRemoved { name: "Legacy" },
+            },
+            Issue {
+                path: ".inner{}.limit".into(),
+                kind: IssueKind::LostNothing,
+            },
+            Issue {
+                path: ".required".into(),
+                kind: IssueKind::BecameRequired,
+            },
+            Issue {
+                path: ".required".into(),
+                kind: IssueKind::NoNothingValue,
+            },
+            // Has nothing value, but plain `serde` still requires it.
+            Issue {
+                path: ".port".into(),
+                kind: IssueKind::BecameRequired,
+            },
+            // Missing field gets its default, only filling from `Nothing` fails.
+            Issue {
+                path: ".threads".into(),
+                kind: IssueKind::NoNothingValue,
+            },
+        ]
+    );
+}
@@ -8,36 +8,41 @@ use serde::{
     Deserialize, Deserializer,
 };
 
-use crate::Nothing;
+use crate::{registry, Nothing};
 
-struct FewNothing {
-    len: usize,
+/// Sequence of `len` values produced by stateless deserializer `de`.
+pub(crate) struct Few<D> {
+    pub(crate) de: D,
+    pub(crate) len: usize,
 }
 
-impl<'de> SeqAccess<'de> for FewNothing {
-    type Error = NothingDeserializeError;
+impl<'de, D> SeqAccess<'de> for Few<D>
+where
+    D: Deserializer<'de> + Copy,
+{
+    type Error = D::Error;
 
     #[inline]
-    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, NothingDeserializeError>
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, D::Error>
     where
         T: DeserializeSeed<'de>,
     {
         if self.len > 0 {
             self.len -= 1;
-            seed.deserialize(Nothing).map(Some)
+            seed.deserialize(self.de).map(Some)
         } else {
             Ok(None)
         }
     }
 
     #[inline]
-    fn next_element<T>(&mut self) -> Result<Option<T>, NothingDeserializeError>
+    fn next_element<T>(&mut self) -> Result<Option<T>, D::Error>
     where
         T: Deserialize<'de>,
     {
         if self.len > 0 {
             self.len -= 1;
-            T::deserialize(Nothing).map(Some)
+            T::deserialize(self.de).map(Some)
         } else {
             Ok(None)
         }
@@ -282,25 +287,31 @@ impl<'de> Deserializer<'de> for Nothing {
     #[inline]
     fn deserialize_unit_struct<V>(
         self,
-        _: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, NothingDeserializeError>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_unit()
+        match registry::lookup(name) {
+            None => visitor.visit_unit(),
+            Some(rule) => registry::deserializer(rule)?.deserialize_unit_struct(name, visitor),
+        }
     }
 
     #[inline]
     fn deserialize_newtype_struct<V>(
         self,
-        _: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, NothingDeserializeError>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        match registry::lookup(name) {
+            None => visitor.visit_newtype_struct(self),
+            Some(rule) => visitor.visit_newtype_struct(registry::deserializer(rule)?),
+        }
     }
 
     #[inline]
@@ -320,20 +331,23 @@ impl<'de> Deserializer<'de> for Nothing {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(FewNothing { len })
+        visitor.visit_seq(Few { de: Nothing, len })
     }
 
     #[inline]
     fn deserialize_tuple_struct<V>(
         self,
-        _: &'static str,
+        name: &'static str,
         len: usize,
         visitor: V,
     ) -> Result<V::Value, NothingDeserializeError>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(FewNothing { len })
+        match registry::lookup(name) {
+            None => visitor.visit_seq(Few { de: Nothing, len }),
+            Some(_) => Err(NothingDeserializeError),
+        }
     }
 
     #[inline]
@@ -347,27 +361,36 @@ impl<'de> Deserializer<'de> for Nothing {
     #[inline]
     fn deserialize_struct<V>(
         self,
-        _: &'static str,
+        name: &'static str,
         fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, NothingDeserializeError>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(FewNothing { len: fields.len() })
+        match registry::lookup(name) {
+            None => visitor.visit_seq(Few {
+                de: Nothing,
+                len: fields.len(),
+            }),
+            Some(rule) => visitor.visit_seq(registry::struct_access(rule, fields)?),
+        }
     }
 
     #[inline]
     fn deserialize_enum<V>(
         self,
-        _: &'static str,
+        name: &'static str,
         _: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, NothingDeserializeError>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_enum(self)
+        match registry::lookup(name) {
+            None => visitor.visit_enum(self),
+            Some(_) => Err(NothingDeserializeError),
+        }
     }
 
     #[inline]
@@ -528,7 +551,7 @@ impl<'de> VariantAccess<'de> for Nothing {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(FewNothing { len })
+        visitor.visit_seq(Few { de: Nothing, len })
     }
 
     #[inline]
@@ -540,7 +563,10 @@ impl<'de> VariantAccess<'de> for Nothing {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(FewNothing { len: fields.len() })
+        visitor.visit_seq(Few {
+            de: Nothing,
+            len: fields.len(),
+        })
     }
 }
 
@@ -0,0 +1,513 @@
+//!
+//! Structural comparison of serializable values.
+//!
+//! [`diff`] and [`structurally_eq`] compare values by what they serialize to,
+//! so they work for any `Serialize` type without `PartialEq` bounds.
+//! Values are compared as [`Value`] trees,
+//! structs and maps entry by entry, sequences element by element.
+//! `NaN` floats are equal to each other, so a value is always equal to itself.
+//!
+//! With [`DiffOptions::absent_as_nothing`] a skipped field or map entry
+//! is equal to a nothing one, same as reading it back would make it.
+//!
+
+use alloc::{
+    borrow::{Cow, ToOwned},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Write};
+
+use serde::Serialize;
+
+use crate::value::{to_value, Value, ValueError};
+
+/// Options of [`diff_with`] and [`structurally_eq_with`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DiffOptions {
+    /// Treat missing struct fields and map entries as nothing.
+    pub absent_as_nothing: bool,
+}
+
+/// Difference at some path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// Path to the changed part, like `.field[0]::Variant.inner`.
+    /// Empty for the value itself.
+    pub path: String,
+
+    /// Rendering of the old part, `None` if it is missing.
+    pub old: Option<String>,
+
+    /// Rendering of the new part, `None` if it is missing.
+    pub new: Option<String>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "`{}`: ", self.path)?;
+        }
+        let old = self.old.as_deref().unwrap_or("absent");
+        let new = self.new.as_deref().unwrap_or("absent");
+        write!(f, "{} -> {}", old, new)
+    }
+}
+
+/// Lists differences between two values with default options.
+///
+/// ```
+/// #[derive(serde_derive::Serialize)]
+/// struct Config {
+///     name: String,
+///     ports: Vec<u16>,
+/// }
+///
+/// let old = Config { name: "web".into(), ports: vec![80] };
+/// let new = Config { name: "web".into(), ports: vec![80, 443] };
+///
+/// let changes = serde_nothing::diff(&old, &new).unwrap();
+/// assert_eq!(changes.len(), 1);
+/// assert_eq!(changes[0].to_string(), "`.ports[1]`: absent -> 443");
+/// ```
+pub fn diff<A, B>(old: &A, new: &B) -> Result<Vec<Change>, ValueError>
+where
+    A: ?Sized + Serialize,
+    B: ?Sized + Serialize,
+{
+    diff_with(old, new, DiffOptions::default())
+}
+
+/// Lists differences between two values.
+pub fn diff_with<A, B>(old: &A, new: &B, options: DiffOptions) -> Result<Vec<Change>, ValueError>
+where
+    A: ?Sized + Serialize,
+    B: ?Sized + Serialize,
+{
+    let old = to_value(old)?;
+    let new = to_value(new)?;
+    let mut changes = Vec::new();
+    compare(&old, &new, options, &mut String::new(), &mut changes);
+    Ok(changes)
+}
+
+/// Returns true if both values serialize to the same tree, with default options.
+/// Returns false if any of them fails to serialize.
+///
+/// ```
+/// #[derive(serde_derive::Serialize)]
+/// struct Old {
+///     name: String,
+///     #[serde(skip_serializing_if = "Option::is_none")]
+///     port: Option<u16>,
+/// }
+///
+/// #[derive(serde_derive::Serialize)]
+/// struct New {
+///     name: String,
+///     port: Option<u16>,
+/// }
+///
+/// # use serde_nothing::diff::{structurally_eq_with, DiffOptions};
+/// let old = Old { name: "web".into(), port: None };
+/// let new = New { name: "web".into(), port: None };
+///
+/// assert!(!serde_nothing::structurally_eq(&old, &new));
+/// assert!(structurally_eq_with(&old, &new, DiffOptions { absent_as_nothing: true }));
+/// ```
+pub fn structurally_eq<A, B>(a: &A, b: &B) -> bool
+where
+    A: ?Sized + Serialize,
+    B: ?Sized + Serialize,
+{
+    structurally_eq_with(a, b, DiffOptions::default())
+}
+
+/// Returns true if both values serialize to the same tree.
+/// Returns false if any of them fails to serialize.
+pub fn structurally_eq_with<A, B>(a: &A, b: &B, options: DiffOptions) -> bool
+where
+    A: ?Sized + Serialize,
+    B: ?Sized + Serialize,
+{
+    matches!(diff_with(a, b, options), Ok(changes) if changes.is_empty())
+}
+
+fn compare(
+    old: &Value,
+    new: &Value,
+    options: DiffOptions,
+    path: &mut String,
+    out: &mut Vec<Change>,
+) {
+    if old == new {
+        return;
+    }
+
+    match (old, new) {
+        (Value::F64(old), Value::F64(new)) if old.is_nan() && new.is_nan() => {}
+        (Value::Some(old), Value::Some(new)) | (Value::Newtype(old), Value::Newtype(new)) => {
+            compare(old, new, options, path, out)
+        }
+        (Value::Declared(old_nothing, old), Value::Declared(new_nothing, new))
+            if old_nothing == new_nothing =>
+        {
+            compare(old, new, options, path, out)
+        }
+        (Value::Seq(old), Value::Seq(new)) => compare_elements(old, new, options, path, out),
+        (Value::Tuple(old), Value::Tuple(new)) if old.len() == new.len() => {
+            compare_elements(old, new, options, path, out)
+        }
+        (Value::Struct(old), Value::Struct(new)) => {
+            let segment = |key: &String| format!(".{}", key);
+            compare_entries(old, new, segment, options, path, out)
+        }
+        (Value::Map(old), Value::Map(new)) => {
+            let segment = |key: &Value| format!("[{}]", Render(key));
+            compare_entries(old, new, segment, options, path, out)
+        }
+        (Value::Variant(old_name, old), Value::Variant(new_name, new)) if old_name == new_name => {
+            let len = path.len();
+            path.push_str("::");
+            path.push_str(old_name);
+            compare(old, new, options, path, out);
+            path.truncate(len);
+        }
+        (old, new) => push(out, path, Some(old), Some(new)),
+    }
+}
+
+fn compare_elements(
+    old: &[Value],
+    new: &[Value],
+    options: DiffOptions,
+    path: &mut String,
+    out: &mut Vec<Change>,
+) {
+    for index in 0..old.len().max(new.len()) {
+        let len = path.len();
+        let _ = write!(path, "[{}]", index);
+        match (old.get(index), new.get(index)) {
+            (Some(old), Some(new)) => compare(old, new, options, path, out),
+            (old, new) => push(out, path, old, new),
+        }
+        path.truncate(len);
+    }
+}
+
+fn compare_entries<K>(
+    old: &[(K, Value)],
+    new: &[(K, Value)],
+    segment: impl Fn(&K) -> String,
+    options: DiffOptions,
+    path: &mut String,
+    out: &mut Vec<Change>,
+) where
+    K: Key,
+{
+    // Missing entry with nothing value is not a change with `absent_as_nothing`.
+    let reported = |value: &Value| !(options.absent_as_nothing && value.is_nothing());
+
+    let old_index = Index::new(old);
+    let new_index = Index::new(new);
+
+    for (key, old) in old {
+        let len = path.len();
+        path.push_str(&segment(key));
+        match new_index.find(key) {
+            Some(new) => compare(old, new, options, path, out),
+            None if reported(old) => push(out, path, Some(old), None),
+            None => {}
+        }
+        path.truncate(len);
+    }
+
+    for (key, new) in new {
+        if old_index.find(key).is_none() && reported(new) {
+            let len = path.len();
+            path.push_str(&segment(key));
+            push(out, path, None, Some(new));
+            path.truncate(len);
+        }
+    }
+}
+
+/// Key of struct field or map entry.
+trait Key {
+    /// Returns string to sort keys by, equal keys have equal sort keys.
+    fn sort_key(&self) -> Cow<'_, str>;
+
+    /// Returns true if keys are equal.
+    fn same(&self, other: &Self) -> bool;
+}
+
+impl Key for String {
+    fn sort_key(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl Key for Value {
+    fn sort_key(&self) -> Cow<'_, str> {
+        Cow::Owned(Render(self).to_string())
+    }
+
+    // Different keys may render the same, like newtypes and their content.
+    fn same(&self, other: &Self) -> bool {
+        let mut changes = Vec::new();
+        let options = DiffOptions::default();
+        compare(self, other, options, &mut String::new(), &mut changes);
+        changes.is_empty()
+    }
+}
+
+/// Entries sorted by key, so lookups do not scan every entry.
+struct Index<'a, K> {
+    entries: &'a [(K, Value)],
+    sorted: Vec<(Cow<'a, str>, usize)>,
+}
+
+impl<'a, K> Index<'a, K>
+where
+    K: Key,
+{
+    fn new(entries: &'a [(K, Value)]) -> Self {
+        let mut sorted: Vec<_> = entries
+            .iter()
+            .enumerate()
+            .map(|(index, (key, _))| (key.sort_key(), index))
+            .collect();
+        sorted.sort();
+        Index { entries, sorted }
+    }
+
+    /// Returns value of the first entry with the key.
+    fn find(&self, key: &K) -> Option<&'a Value> {
+        let sort_key = key.sort_key();
+        let start = self.sorted.partition_point(|(k, _)| *k < sort_key);
+        self.sorted[start..]
+            .iter()
+            .take_while(|(k, _)| *k == sort_key)
+            .map(|&(_, index)| &self.entries[index])
+            .find(|(k, _)| k.same(key))
+            .map(|(_, value)| value)
+    }
+}
+
+fn push(out: &mut Vec<Change>, path: &str, old: Option<&Value>, new: Option<&Value>) {
+    out.push(Change {
+        path: path.to_owned(),
+        old: old.map(|v| Render(v).to_string()),
+        new: new.map(|v| Render(v).to_string()),
+    });
+}
+
+/// Compact rendering of a value, close to `Debug` of the original type.
+struct Render<'a>(&'a Value);
+
+impl fmt::Display for Render<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Value::Bool(v) => fmt::Display::fmt(v, f),
+            Value::I64(v) => fmt::Display::fmt(v, f),
+            Value::U64(v) => fmt::Display::fmt(v, f),
+            Value::I128(v) => fmt::Display::fmt(v, f),
+            Value::U128(v) => fmt::Display::fmt(v, f),
+            Value::F64(v) => fmt::Debug::fmt(v, f),
+            Value::Char(v) => fmt::Debug::fmt(v, f),
+            Value::String(v) => fmt::Debug::fmt(v, f),
+            Value::Bytes(v) => fmt::Debug::fmt(v, f),
+            Value::Unit => f.write_str("()"),
+            Value::None => f.write_str("None"),
+            Value::Some(v) => write!(f, "Some({})", Render(v)),
+            Value::Newtype(v) | Value::Declared(_, v) => fmt::Display::fmt(&Render(v), f),
+            Value::Seq(v) => list(f, "[", v, "]"),
+            Value::Tuple(v) => list(f, "(", v, ")"),
+            Value::Map(v) => {
+                f.write_str("{")?;
+                for (index, (key, value)) in v.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", Render(key), Render(value))?;
+                }
+                f.write_str("}")
+            }
+            Value::Struct(v) => {
+                f.write_str("{")?;
+                for (index, (key, value)) in v.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", key, Render(value))?;
+                }
+                f.write_str("}")
+            }
+            Value::Variant(name, payload) => match &**payload {
+                Value::Unit => f.write_str(name),
+                Value::Tuple(v) => {
+                    f.write_str(name)?;
+                    list(f, "(", v, ")")
+                }
+                Value::Struct(_) => write!(f, "{} {}", name, Render(payload)),
+                payload => write!(f, "{}({})", name, Render(payload)),
+            },
+        }
+    }
+}
+
+fn list(f: &mut fmt::Formatter, open: &str, elements: &[Value], close: &str) -> fmt::Result {
+    f.write_str(open)?;
+    for (index, element) in elements.iter().enumerate() {
+        if index > 0 {
+            f.write_str(", ")?;
+        }
+        fmt::Display::fmt(&Render(element), f)?;
+    }
+    f.write_str(close)
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_diff() {
+    use std::collections::BTreeMap;
+
+    #[derive(serde_derive::Serialize)]
+    enum Mode {
+        Off,
+        Range { min: u32, max: u32 },
+    }
+
+    #[derive(serde_derive::Serialize)]
+    struct Config {
+        name: String,
+        ports: Vec<u16>,
+        limits: BTreeMap<String, (u32, u32)>,
+        parent: Option<Box<Config>>,
+        mode: Mode,
+        #[serde(skip_serializing_if = "String::is_empty")]
+        note: String,
+    }
+
+    let parent = || Config {
+        name: "base".into(),
+        ports: vec![],
+        limits: BTreeMap::new(),
+        parent: None,
+        mode: Mode::Off,
+        note: String::new(),
+    };
+
+    let old = Config {
+        name: "web".into(),
+        ports: vec![80, 443],
+        limits: BTreeMap::from([("cpu".into(), (1, 2)), ("mem".into(), (3, 4))]),
+        parent: Some(Box::new(parent())),
+        mode: Mode::Range { min: 1, max: 10 },
+        note: "old".into(),
+    };
+
+    assert!(structurally_eq(&old, &old));
+    assert_eq!(diff(&old, &old).unwrap(), []);
+
+    let new = Config {
+        name: "web".into(),
+        ports: vec![8080],
+        limits: BTreeMap::from([("cpu".into(), (1, 3)), ("disk".into(), (5, 6))]),
+        parent: Some(Box::new(Config {
+            name: "root".into(),
+            ..parent()
+        })),
+        mode: Mode::Range { min: 1, max: 20 },
+        note: String::new(),
+    };
+
+    let changes: Vec<String> = diff(&old, &new)
+        .unwrap()
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            "`.ports[0]`: 80 -> 8080",
+            "`.ports[1]`: 443 -> absent",
+            "`.limits[\"cpu\"][1]`: 2 -> 3",
+            "`.limits[\"mem\"]`: (3, 4) -> absent",
+            "`.limits[\"disk\"]`: absent -> (5, 6)",
+            "`.parent.name`: \"base\" -> \"root\"",
+            "`.mode::Range.max`: 10 -> 20",
+            "`.note`: \"old\" -> absent",
+        ]
+    );
+
+    let changes = diff(
+        &parent(),
+        &Config {
+            mode: Mode::Range { min: 0, max: 1 },
+            ..parent()
+        },
+    )
+    .unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].path, ".mode");
+    assert_eq!(changes[0].old.as_deref(), Some("Off"));
+    assert_eq!(changes[0].new.as_deref(), Some("Range {min: 0, max: 1}"));
+
+    // Skipped field is equal to nothing one only with `absent_as_nothing`.
+    #[derive(serde_derive::Serialize)]
+    struct Note {
+        note: String,
+    }
+
+    #[derive(serde_derive::Serialize)]
+    struct Skipped {
+        #[serde(skip_serializing_if = "String::is_empty")]
+        note: String,
+    }
+
+    let options = DiffOptions {
+        absent_as_nothing: true,
+    };
+    let note = Note {
+        note: String::new(),
+    };
+    let skipped = Skipped {
+        note: String::new(),
+    };
+    assert!(!structurally_eq(&note, &skipped));
+    assert!(structurally_eq_with(&note, &skipped, options));
+    assert!(structurally_eq_with(&skipped, &note, options));
+
+    let note = Note { note: "a".into() };
+    assert!(!structurally_eq_with(&note, &skipped, options));
+
+    // `NaN` is equal to itself, also as a map key.
+    let map = |key| Value::Map(vec![(Value::F64(key), Value::U64(1))]);
+    assert!(structurally_eq(
+        &(f64::NAN, map(f64::NAN)),
+        &(f64::NAN, map(f64::NAN))
+    ));
+    assert!(!structurally_eq(
+        &(f64::NAN, map(f64::NAN)),
+        &(0.0, map(f64::NAN))
+    ));
+    assert_eq!(diff(&map(f64::NAN), &map(0.0)).unwrap().len(), 2);
+
+    // Entries are matched by key regardless of order.
+    let old: Vec<(u32, u32)> = (0..1000).map(|k| (k, k)).collect();
+    let new: Vec<(u32, u32)> = (0..1000)
+        .rev()
+        .map(|k| (k, k + (k == 500) as u32))
+        .collect();
+    let old: std::collections::HashMap<_, _> = old.into_iter().collect();
+    let new: std::collections::HashMap<_, _> = new.into_iter().collect();
+    let changes = diff(&old, &new).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].to_string(), "`[500]`: 500 -> 501");
+}
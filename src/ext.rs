@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Nothing, NothingDeserializeError};
+
+/// Extension trait to check nothingness with method syntax.
+///
+/// Implemented for all `Serialize` types.
+///
+/// Method has the same name as `IsNothing::is_nothing` and returns the same result.
+/// With both traits in scope the call is ambiguous for types that implement both,
+/// call it as `IsNothing::is_nothing(&value)` or `IsNothingExt::is_nothing(&value)` then.
+pub trait IsNothingExt: Serialize {
+    /// Returns true if the value matches definition of "nothing".
+    /// See `is_nothing` function.
+    #[inline]
+    fn is_nothing(&self) -> bool {
+        crate::is_nothing(self)
+    }
+}
+
+impl<T> IsNothingExt for T where T: ?Sized + Serialize {}
+
+/// Extension trait to create "nothing" values with associated function syntax.
+///
+/// Implemented for all `Deserialize` types.
+/// For `DeserializeOwned` types it is implemented for any `'de`,
+/// so `T: DeserializeOwned` bound is enough to call `T::nothing()`.
+pub trait FromNothingExt<'de>: Deserialize<'de> {
+    /// Returns "nothing" value of the type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type has no "nothing" value.
+    /// Use `try_nothing` to handle this case.
+    #[inline]
+    fn nothing() -> Self {
+        match Self::try_nothing() {
+            Ok(value) => value,
+            Err(_) => panic!("Type has no \"nothing\" value"),
+        }
+    }
+
+    /// Returns "nothing" value of the type.
+    /// Or error if failed to create one.
+    #[inline]
+    fn try_nothing() -> Result<Self, NothingDeserializeError> {
+        Self::deserialize(Nothing)
+    }
+}
+
+impl<'de, T> FromNothingExt<'de> for T where T: Deserialize<'de> {}
+
+#[test]
+fn test_is_nothing_ext() {
+    use crate::IsNothing;
+
+    struct Id(u32);
+
+    impl Serialize for Id {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_u32(self.0)
+        }
+    }
+
+    // Only `IsNothingExt` applies to types without `IsNothing`.
+    assert!(Id(0).is_nothing());
+    assert!(!Id(1).is_nothing());
+
+    assert!(IsNothingExt::is_nothing(&0u32));
+    assert!(IsNothingExt::is_nothing(""));
+    assert!(!IsNothingExt::is_nothing(&(0, "a")));
+    assert_eq!(
+        IsNothing::is_nothing(&5u32),
+        IsNothingExt::is_nothing(&5u32)
+    );
+}
+
+#[test]
+fn test_from_nothing_ext() {
+    fn owned<T: serde::de::DeserializeOwned>() -> T {
+        T::nothing()
+    }
+
+    assert_eq!(u32::nothing(), 0);
+    assert_eq!(<&str>::try_nothing(), Ok(""));
+    assert_eq!(owned::<(u8, bool)>(), (0, false));
+    assert!(core::num::NonZeroU32::try_nothing().is_err());
+}
+
+#[test]
+#[should_panic]
+fn test_from_nothing_ext_fail() {
+    core::num::NonZeroU32::nothing();
+}
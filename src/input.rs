@@ -0,0 +1,200 @@
+use core::fmt;
+
+use serde::{
+    de::{DeserializeSeed, EnumAccess, IgnoredAny, MapAccess, SeqAccess, VariantAccess, Visitor},
+    Deserializer,
+};
+
+/// Seed that checks whether self-describing input represents nothing
+/// without building any value.
+///
+/// Input is walked with `deserialize_any`.
+/// `null`, `false`, zero, empty strings and containers with only nothing elements
+/// or values are nothing.
+/// Maps are checked the same way as structs, only values are considered, not keys,
+/// because self-describing input does not tell one from another.
+///
+/// Whole input is consumed even after non-nothing part is found.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IsNothingSeed;
+
+impl<'de> DeserializeSeed<'de> for IsNothingSeed {
+    type Value = bool;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(NothingVisitor)
+    }
+}
+
+/// Visitor that produces `true` if visited value is nothing.
+///
+/// See [`IsNothingSeed`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NothingVisitor;
+
+impl<'de> Visitor<'de> for NothingVisitor {
+    type Value = bool;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    #[inline]
+    fn visit_bool<E>(self, v: bool) -> Result<bool, E> {
+        Ok(!v)
+    }
+
+    #[inline]
+    fn visit_i64<E>(self, v: i64) -> Result<bool, E> {
+        Ok(v == 0)
+    }
+
+    #[inline]
+    fn visit_i128<E>(self, v: i128) -> Result<bool, E> {
+        Ok(v == 0)
+    }
+
+    #[inline]
+    fn visit_u64<E>(self, v: u64) -> Result<bool, E> {
+        Ok(v == 0)
+    }
+
+    #[inline]
+    fn visit_u128<E>(self, v: u128) -> Result<bool, E> {
+        Ok(v == 0)
+    }
+
+    #[inline]
+    fn visit_f64<E>(self, v: f64) -> Result<bool, E> {
+        Ok(v == 0.0)
+    }
+
+    #[inline]
+    fn visit_char<E>(self, v: char) -> Result<bool, E> {
+        Ok(v == '\0')
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<bool, E> {
+        Ok(v.is_empty())
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<bool, E> {
+        Ok(v.is_empty())
+    }
+
+    #[inline]
+    fn visit_none<E>(self) -> Result<bool, E> {
+        Ok(true)
+    }
+
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // `Some` is never nothing, same as for `Nothing` serializer.
+        deserializer.deserialize_ignored_any(IgnoredAny)?;
+        Ok(false)
+    }
+
+    #[inline]
+    fn visit_unit<E>(self) -> Result<bool, E> {
+        Ok(true)
+    }
+
+    #[inline]
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        IsNothingSeed.deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<bool, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut nothing = true;
+        while nothing {
+            match seq.next_element_seed(IsNothingSeed)? {
+                None => return Ok(true),
+                Some(element) => nothing = element,
+            }
+        }
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(false)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<bool, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut nothing = true;
+        while nothing {
+            match map.next_key::<IgnoredAny>()? {
+                None => return Ok(true),
+                Some(IgnoredAny) => nothing = map.next_value_seed(IsNothingSeed)?,
+            }
+        }
+        while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+        Ok(false)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<bool, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        // Any variant with nothing payload is nothing.
+        let (IgnoredAny, variant) = data.variant()?;
+        variant.newtype_variant_seed(IsNothingSeed)
+    }
+}
+
+/// Checks whether self-describing input represents nothing.
+///
+/// ```
+/// let mut de = serde_json::Deserializer::from_str(r#"{"a": [0, ""], "b": null}"#);
+/// assert!(serde_nothing::is_nothing_input(&mut de).unwrap());
+///
+/// let mut de = serde_json::Deserializer::from_str(r#"{"a": [0, "x"], "b": null}"#);
+/// assert!(!serde_nothing::is_nothing_input(&mut de).unwrap());
+/// ```
+#[inline]
+pub fn is_nothing_input<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    IsNothingSeed.deserialize(deserializer)
+}
+
+#[test]
+fn test_is_nothing_input() {
+    fn check(json: &str) -> bool {
+        let mut de = serde_json::Deserializer::from_str(json);
+        let nothing = is_nothing_input(&mut de).unwrap();
+        de.end().unwrap();
+        nothing
+    }
+
+    assert!(check("null"));
+    assert!(check("0"));
+    assert!(check("-0.0"));
+    assert!(check("false"));
+    assert!(check(r#""""#));
+    assert!(check("[]"));
+    assert!(check("{}"));
+    assert!(check(r#"[0, null, {"a": [], "b": {"c": false}}]"#));
+
+    assert!(!check("1"));
+    assert!(!check("0.5"));
+    assert!(!check("true"));
+    assert!(!check(r#""a""#));
+    assert!(!check(r#"[0, 1, [2, 3], {"a": 4}]"#));
+    assert!(!check(r#"{"a": 0, "b": {"c": "d"}, "e": [1]}"#));
+}
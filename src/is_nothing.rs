@@ -0,0 +1,597 @@
+use core::{
+    marker::PhantomData,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping,
+    },
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Direct check for nothingness.
+///
+/// Implementations must agree with `is_nothing` function,
+/// that is, `IsNothing::is_nothing(&value) == serde_nothing::is_nothing(&value)`
+/// for any value of a type that implements both `IsNothing` and `Serialize`.
+///
+/// The trait exists for performance only.
+/// `is_nothing` function runs whole `Serialize` implementation against `Nothing` serializer,
+/// while implementations of this trait check fields directly.
+///
+/// With `derive` feature this trait can be derived for structs and enums.
+/// Derived implementation uses `IsNothing` implementation of each field's type
+/// when there is one and falls back to `is_nothing` function otherwise.
+/// Fields with types that mention type parameters always use `IsNothing`,
+/// wrap type arguments without one in [`BySerde`].
+///
+/// Rules from [`registry`](crate::registry) change what `is_nothing` function returns.
+/// Once any rule is registered, derived implementation checks the value
+/// with `is_nothing` function if the type implements `Serialize`.
+/// Generic types are checked this way only if `Serialize` is implemented
+/// without bounds on type parameters, otherwise fields are checked one by one
+/// and a rule for the generic type itself is not applied.
+/// Manual implementations for named types that may have rules should do the same.
+pub trait IsNothing {
+    /// Returns true if the value matches definition of "nothing".
+    fn is_nothing(&self) -> bool;
+}
+
+/// Wrapper that checks the value with `is_nothing` function.
+///
+/// Blanket fallback for types without `IsNothing` implementation:
+/// `IsNothing` is implemented for `BySerde<T>` with any `T: Serialize`.
+/// Serialized and deserialized transparently,
+/// so it can be used as type argument for generic types with derived `IsNothing`.
+///
+/// ```
+/// # use serde_nothing::{BySerde, IsNothing};
+/// struct Id(u32);
+///
+/// impl serde::Serialize for Id {
+///     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+///         serializer.serialize_u32(self.0)
+///     }
+/// }
+///
+/// #[derive(serde_derive::Serialize, serde_nothing_derive::IsNothing)]
+/// struct Record<T> {
+///     id: T,
+/// }
+///
+/// assert!(Record { id: BySerde(Id(0)) }.is_nothing());
+/// assert!(!Record { id: BySerde(Id(1)) }.is_nothing());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct BySerde<T: ?Sized>(pub T);
+
+impl<T: ?Sized> Deref for BySerde<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> DerefMut for BySerde<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: ?Sized> IsNothing for BySerde<T>
+where
+    T: Serialize,
+{
+    #[inline]
+    fn is_nothing(&self) -> bool {
+        crate::is_nothing(&self.0)
+    }
+}
+
+impl<T: ?Sized> Serialize for BySerde<T>
+where
+    T: Serialize,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for BySerde<T>
+where
+    T: Deserialize<'de>,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(BySerde)
+    }
+}
+
+macro_rules! impl_zero {
+    ($($ty:ty => $zero:expr),* $(,)?) => {
+        $(
+            impl IsNothing for $ty {
+                #[inline]
+                fn is_nothing(&self) -> bool {
+                    *self == $zero
+                }
+            }
+        )*
+    };
+}
+
+impl_zero! {
+    bool => false,
+    i8 => 0, i16 => 0, i32 => 0, i64 => 0, i128 => 0, isize => 0,
+    u8 => 0, u16 => 0, u32 => 0, u64 => 0, u128 => 0, usize => 0,
+    f32 => 0.0, f64 => 0.0,
+    char => '\0',
+}
+
+macro_rules! impl_never {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl IsNothing for $ty {
+                #[inline]
+                fn is_nothing(&self) -> bool {
+                    false
+                }
+            }
+        )*
+    };
+}
+
+impl_never! {
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize,
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+}
+
+impl IsNothing for str {
+    #[inline]
+    fn is_nothing(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl IsNothing for () {
+    #[inline]
+    fn is_nothing(&self) -> bool {
+        true
+    }
+}
+
+impl<T: ?Sized> IsNothing for PhantomData<T> {
+    #[inline]
+    fn is_nothing(&self) -> bool {
+        true
+    }
+}
+
+impl<T> IsNothing for Option<T> {
+    #[inline]
+    fn is_nothing(&self) -> bool {
+        self.is_none()
+    }
+}
+
+impl<T> IsNothing for [T] {
+    #[inline]
+    fn is_nothing(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<T, const N: usize> IsNothing for [T; N]
+where
+    T: IsNothing,
+{
+    #[inline]
+    fn is_nothing(&self) -> bool {
+        self.iter().all(T::is_nothing)
+    }
+}
+
+impl<T> IsNothing for Wrapping<T>
+where
+    T: IsNothing,
+{
+    #[inline]
+    fn is_nothing(&self) -> bool {
+        self.0.is_nothing()
+    }
+}
+
+impl IsNothing for Duration {
+    #[inline]
+    fn is_nothing(&self) -> bool {
+        *self == Duration::ZERO
+    }
+}
+
+impl<T: ?Sized + IsNothing> IsNothing for &T {
+    #[inline]
+    fn is_nothing(&self) -> bool {
+        T::is_nothing(*self)
+    }
+}
+
+impl<T: ?Sized + IsNothing> IsNothing for &mut T {
+    #[inline]
+    fn is_nothing(&self) -> bool {
+        T::is_nothing(*self)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident)+) => {
+        impl<$($name),+> IsNothing for ($($name,)+)
+        where
+            $($name: IsNothing,)+
+        {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn is_nothing(&self) -> bool {
+                let ($($name,)+) = self;
+                true $(&& $name.is_nothing())+
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A B);
+impl_tuple!(A B C);
+impl_tuple!(A B C D);
+impl_tuple!(A B C D E);
+impl_tuple!(A B C D E F);
+impl_tuple!(A B C D E F G);
+impl_tuple!(A B C D E F G H);
+impl_tuple!(A B C D E F G H I);
+impl_tuple!(A B C D E F G H I J);
+impl_tuple!(A B C D E F G H I J K);
+impl_tuple!(A B C D E F G H I J K L);
+impl_tuple!(A B C D E F G H I J K L M);
+impl_tuple!(A B C D E F G H I J K L M N);
+impl_tuple!(A B C D E F G H I J K L M N O);
+impl_tuple!(A B C D E F G H I J K L M N O P);
+
+#[cfg(feature = "alloc")]
+mod alloc_impls {
+    use alloc::{
+        borrow::{Cow, ToOwned},
+        boxed::Box,
+        collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque},
+        ffi::CString,
+        rc::Rc,
+        string::String,
+        sync::Arc,
+        vec::Vec,
+    };
+    use core::ffi::CStr;
+
+    use super::IsNothing;
+
+    macro_rules! impl_empty {
+        ($(<$($param:ident),*> $ty:ty),* $(,)?) => {
+            $(
+                impl<$($param),*> IsNothing for $ty {
+                    #[inline]
+                    fn is_nothing(&self) -> bool {
+                        self.is_empty()
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_empty! {
+        <> String,
+        <> CString,
+        <T> Vec<T>,
+        <T> VecDeque<T>,
+        <T> LinkedList<T>,
+        <T> BinaryHeap<T>,
+        <T> BTreeSet<T>,
+        <K, V> BTreeMap<K, V>,
+    }
+
+    impl IsNothing for CStr {
+        #[inline]
+        fn is_nothing(&self) -> bool {
+            self.to_bytes().is_empty()
+        }
+    }
+
+    impl<T: ?Sized + IsNothing> IsNothing for Box<T> {
+        #[inline]
+        fn is_nothing(&self) -> bool {
+            T::is_nothing(self)
+        }
+    }
+
+    impl<T: ?Sized + IsNothing> IsNothing for Rc<T> {
+        #[inline]
+        fn is_nothing(&self) -> bool {
+            T::is_nothing(self)
+        }
+    }
+
+    impl<T: ?Sized + IsNothing> IsNothing for Arc<T> {
+        #[inline]
+        fn is_nothing(&self) -> bool {
+            T::is_nothing(self)
+        }
+    }
+
+    impl<T: ?Sized + IsNothing + ToOwned> IsNothing for Cow<'_, T> {
+        #[inline]
+        fn is_nothing(&self) -> bool {
+            T::is_nothing(self)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+mod std_impls {
+    use std::{
+        collections::{HashMap, HashSet},
+        ffi::{OsStr, OsString},
+        path::{Path, PathBuf},
+    };
+
+    use super::IsNothing;
+
+    macro_rules! impl_empty {
+        ($(<$($param:ident),*> $ty:ty),* $(,)?) => {
+            $(
+                impl<$($param),*> IsNothing for $ty {
+                    #[inline]
+                    fn is_nothing(&self) -> bool {
+                        self.is_empty()
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_empty! {
+        <> OsStr,
+        <> OsString,
+        <T, S> HashSet<T, S>,
+        <K, V, S> HashMap<K, V, S>,
+    }
+
+    impl IsNothing for Path {
+        #[inline]
+        fn is_nothing(&self) -> bool {
+            self.as_os_str().is_empty()
+        }
+    }
+
+    impl IsNothing for PathBuf {
+        #[inline]
+        fn is_nothing(&self) -> bool {
+            self.as_os_str().is_empty()
+        }
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    //! Autoref-based dispatch used by derived `IsNothing` implementations.
+    //!
+    //! `(&Probe(field)).__is_nothing()` resolves to `ViaIsNothing`
+    //! if field type implements `IsNothing`
+    //! and to `ViaSerde` otherwise.
+    //!
+    //! `(&Probe(self)).__registered()` resolves to `ViaRegistry`
+    //! if the type implements `Serialize` and to `ViaFields` otherwise.
+
+    pub use serde::Serialize;
+
+    use super::IsNothing;
+
+    pub struct Probe<'a, T: ?Sized>(pub &'a T);
+
+    pub trait ViaIsNothing {
+        fn __is_nothing(&self) -> bool;
+    }
+
+    impl<T: ?Sized + IsNothing> ViaIsNothing for Probe<'_, T> {
+        #[inline]
+        fn __is_nothing(&self) -> bool {
+            self.0.is_nothing()
+        }
+    }
+
+    pub trait ViaSerde {
+        fn __is_nothing(&self) -> bool;
+    }
+
+    impl<T: ?Sized + Serialize> ViaSerde for &Probe<'_, T> {
+        #[inline]
+        fn __is_nothing(&self) -> bool {
+            crate::is_nothing(self.0)
+        }
+    }
+
+    pub trait ViaRegistry {
+        fn __registered(&self) -> Option<bool>;
+    }
+
+    /// Registry rules may apply to any part of the value,
+    /// so once there are any, the value is checked with `is_nothing` function.
+    impl<T: ?Sized + Serialize> ViaRegistry for Probe<'_, T> {
+        #[inline]
+        fn __registered(&self) -> Option<bool> {
+            if crate::registry::used() {
+                Some(crate::is_nothing(self.0))
+            } else {
+                None
+            }
+        }
+    }
+
+    pub trait ViaFields {
+        fn __registered(&self) -> Option<bool>;
+    }
+
+    impl<T: ?Sized> ViaFields for &Probe<'_, T> {
+        #[inline]
+        fn __registered(&self) -> Option<bool> {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+fn check_agree<T: ?Sized + IsNothing + serde::Serialize>(value: &T) {
+    assert_eq!(value.is_nothing(), crate::is_nothing(value));
+}
+
+#[test]
+fn test_primitives() {
+    check_agree(&0u32);
+    check_agree(&1u32);
+    check_agree(&-0.0f64);
+    check_agree(&f64::NAN);
+    check_agree(&'\0');
+    check_agree(&"");
+    check_agree(&"a");
+    check_agree(&(0, "", None::<u8>));
+    check_agree(&(0, "", Some(0u8)));
+    check_agree(&[0, 0, 0]);
+    check_agree(&[0, 1, 0]);
+    check_agree(&Duration::from_secs(0));
+    check_agree(&Duration::from_nanos(1));
+    check_agree(&NonZeroU32::new(1).unwrap());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_derive() {
+    struct Opaque(u32);
+
+    impl serde::Serialize for Opaque {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(&self.0)
+        }
+    }
+
+    fn is_odd(v: &u32) -> bool {
+        v % 2 == 1
+    }
+
+    #[derive(serde_nothing_derive::IsNothing, serde_derive::Serialize)]
+    struct Record<T> {
+        name: String,
+        tags: Vec<String>,
+        generic: T,
+        opaque: Opaque,
+        #[serde(skip)]
+        _skipped: u32,
+        #[serde(skip_serializing_if = "is_odd")]
+        odd: u32,
+    }
+
+    #[derive(serde_nothing_derive::IsNothing, serde_derive::Serialize)]
+    enum Enum {
+        Unit,
+        Newtype(u32),
+        Struct {
+            a: u32,
+            b: Option<u32>,
+        },
+        #[serde(skip_serializing)]
+        _Skipped,
+    }
+
+    let record = |name: &str, generic: u8, opaque: u32, skipped: u32, odd: u32| Record {
+        name: name.into(),
+        tags: Vec::new(),
+        generic,
+        opaque: Opaque(opaque),
+        _skipped: skipped,
+        odd,
+    };
+
+    for record in [
+        record("", 0, 0, 0, 0),
+        record("", 0, 0, 1, 1),
+        record("a", 0, 0, 0, 0),
+        record("", 1, 0, 0, 0),
+        record("", 0, 1, 0, 0),
+        record("", 0, 0, 0, 2),
+    ] {
+        check_agree(&record);
+    }
+
+    for value in [
+        Enum::Unit,
+        Enum::Newtype(0),
+        Enum::Newtype(1),
+        Enum::Struct { a: 0, b: None },
+        Enum::Struct { a: 0, b: Some(0) },
+        Enum::_Skipped,
+    ] {
+        check_agree(&value);
+    }
+}
+
+#[test]
+fn test_derive_dispatch() {
+    /// Disagrees with its `Serialize` on purpose to observe which one is used.
+    struct Always;
+
+    impl IsNothing for Always {
+        fn is_nothing(&self) -> bool {
+            true
+        }
+    }
+
+    impl serde::Serialize for Always {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bool(true)
+        }
+    }
+
+    // Without `Serialize`, so rules registered by other tests do not change dispatch.
+    #[derive(serde_nothing_derive::IsNothing)]
+    struct Concrete {
+        value: Always,
+    }
+
+    #[derive(serde_nothing_derive::IsNothing)]
+    struct Generic<T> {
+        value: T,
+    }
+
+    #[derive(serde_nothing_derive::IsNothing, serde_derive::Serialize)]
+    struct Wrapped<T> {
+        value: Option<T>,
+    }
+
+    assert!(Concrete { value: Always }.is_nothing());
+    assert!(Generic { value: Always }.is_nothing());
+    assert!(!Generic {
+        value: BySerde(Always)
+    }
+    .is_nothing());
+
+    // Only `Option<T>` must implement `IsNothing`, not `T`.
+    struct Plain;
+    assert!(!Wrapped { value: Some(Plain) }.is_nothing());
+    assert!(Wrapped::<Plain> { value: None }.is_nothing());
+}
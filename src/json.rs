@@ -0,0 +1,487 @@
+//!
+//! Nothing in untyped JSON.
+//!
+//! [`prune_nothing`] removes nothing values from `serde_json::Value`
+//! with the same rules as `Nothing` serializer uses for typed values:
+//! `null`, `false`, zero numbers, empty strings, arrays and objects.
+//! Values are pruned bottom up, so arrays and objects left empty are removed too.
+//!
+//! JSON does not tell tuples from sequences or structs from maps,
+//! so arrays and objects are treated as sequences and maps,
+//! which are nothing only when empty.
+//! Typed value that is nothing serializes into JSON that is pruned entirely,
+//! unless it contains arrays, which keep positions of their elements by default.
+//!
+//! [`merge_patch`] and [`apply_merge_patch`] implement
+//! [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7386) for typed values,
+//! where `null` stands for any nothing value, not only `None`.
+//!
+//! Requires `serde_json` feature.
+//!
+
+use std::{borrow::ToOwned, string::String, vec::Vec};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Error, Map, Value};
+
+use crate::{
+    value::{from_value, to_value, Value as Tree},
+    Nothing,
+};
+
+/// Options of [`prune_nothing_with`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PruneOptions {
+    /// Keep nothing elements of arrays, so positions of other elements do not change.
+    /// Enabled by default, since tuples and arrays are read back by position.
+    pub keep_array_positions: bool,
+
+    /// Treat `false` as a value instead of nothing.
+    pub keep_false: bool,
+}
+
+impl Default for PruneOptions {
+    #[inline]
+    fn default() -> Self {
+        PruneOptions {
+            keep_array_positions: true,
+            keep_false: false,
+        }
+    }
+}
+
+/// Removes nothing values from JSON value with default options.
+///
+/// Root value itself is never removed, even if it is nothing.
+/// Array elements are pruned, but never removed.
+///
+/// ```
+/// let mut value = serde_json::json!({
+///     "name": "a",
+///     "port": 0,
+///     "tags": [],
+///     "limits": { "min": 0.0, "max": null },
+///     "flags": [false, true, ""],
+/// });
+/// serde_nothing::json::prune_nothing(&mut value);
+/// assert_eq!(value, serde_json::json!({ "name": "a", "flags": [false, true, ""] }));
+/// ```
+pub fn prune_nothing(value: &mut Value) {
+    prune_nothing_with(value, PruneOptions::default())
+}
+
+/// Removes nothing values from JSON value.
+///
+/// ```
+/// # use serde_nothing::json::{prune_nothing_with, PruneOptions};
+/// let mut value = serde_json::json!({ "debug": false, "levels": [0, 3, 0] });
+/// prune_nothing_with(&mut value, PruneOptions { keep_array_positions: false, keep_false: true });
+/// assert_eq!(value, serde_json::json!({ "debug": false, "levels": [3] }));
+/// ```
+pub fn prune_nothing_with(value: &mut Value, options: PruneOptions) {
+    match value {
+        Value::Array(elements) => {
+            for element in elements.iter_mut() {
+                prune_nothing_with(element, options);
+            }
+            if !options.keep_array_positions {
+                elements.retain(|element| !is_nothing(element, options));
+            }
+        }
+        Value::Object(entries) => {
+            for (_, entry) in entries.iter_mut() {
+                prune_nothing_with(entry, options);
+            }
+            entries.retain(|_, entry| !is_nothing(entry, options));
+        }
+        _ => {}
+    }
+}
+
+fn is_nothing(value: &Value, options: PruneOptions) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Bool(v) => !*v && !options.keep_false,
+        Value::Number(v) => v.as_f64() == Some(0.0),
+        Value::String(v) => v.is_empty(),
+        Value::Array(v) => v.is_empty(),
+        Value::Object(v) => v.is_empty(),
+    }
+}
+
+/// Returns merge patch that turns `old` into `new`.
+///
+/// Only changed fields are written.
+/// Fields that became nothing or were skipped are written as `null`.
+/// Structs and maps with string keys are patched field by field,
+/// any other changed value is written as a whole.
+///
+/// ```
+/// #[derive(serde_derive::Serialize)]
+/// struct Service {
+///     name: String,
+///     port: u16,
+///     tags: Vec<String>,
+/// }
+///
+/// let old = Service { name: "web".into(), port: 80, tags: vec!["a".into()] };
+/// let new = Service { name: "web".into(), port: 8080, tags: vec![] };
+///
+/// let patch = serde_nothing::json::merge_patch(&old, &new).unwrap();
+/// assert_eq!(patch, serde_json::json!({ "port": 8080, "tags": null }));
+/// ```
+pub fn merge_patch<T>(old: &T, new: &T) -> Result<Value, Error>
+where
+    T: ?Sized + Serialize,
+{
+    let old = to_value(old)?;
+    let new = to_value(new)?;
+    Ok(diff(&old, &new)?.unwrap_or_else(|| Value::Object(Map::new())))
+}
+
+/// Applies merge patch to the target.
+///
+/// `null` resets the field to nothing,
+/// so it works for any field that can be nothing, not only `Option`.
+/// `null` patch resets whole target.
+///
+/// ```
+/// #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+/// struct Service {
+///     name: String,
+///     port: u16,
+///     tags: Vec<String>,
+/// }
+///
+/// let mut service = Service { name: "web".into(), port: 80, tags: vec!["a".into()] };
+/// let patch = serde_json::json!({ "port": 8080, "tags": null });
+///
+/// serde_nothing::json::apply_merge_patch(&mut service, &patch).unwrap();
+/// assert_eq!(service, Service { name: "web".into(), port: 8080, tags: vec![] });
+/// ```
+pub fn apply_merge_patch<T>(target: &mut T, patch: &Value) -> Result<(), Error>
+where
+    T: Serialize + DeserializeOwned,
+{
+    if patch.is_null() {
+        *target = T::deserialize(Nothing).map_err(serde::de::Error::custom)?;
+        return Ok(());
+    }
+
+    let mut value = to_value(&*target)?;
+    apply(&mut value, patch)?;
+    *target = from_value(value)?;
+    Ok(())
+}
+
+/// Returns patch for changed value, `None` if unchanged.
+fn diff(old: &Tree, new: &Tree) -> Result<Option<Value>, Error> {
+    if old == new {
+        return Ok(None);
+    }
+    if new.is_nothing() {
+        return Ok(if old.is_nothing() {
+            None
+        } else {
+            Some(Value::Null)
+        });
+    }
+
+    let (old, new) = match (members(old), members(new)) {
+        (Some(old), Some(new)) => (old, new),
+        _ => return serde_json::to_value(new).map(Some),
+    };
+
+    let mut patch = Map::new();
+    for &(key, value) in &new {
+        match old.iter().find(|(k, _)| *k == key) {
+            Some((_, old)) => {
+                if let Some(value) = diff(old, value)? {
+                    patch.insert(key.to_owned(), value);
+                }
+            }
+            None if value.is_nothing() => {}
+            None => {
+                patch.insert(key.to_owned(), serde_json::to_value(value)?);
+            }
+        }
+    }
+    for &(key, value) in &old {
+        if !value.is_nothing() && !new.iter().any(|(k, _)| *k == key) {
+            patch.insert(key.to_owned(), Value::Null);
+        }
+    }
+
+    Ok(if patch.is_empty() {
+        None
+    } else {
+        Some(Value::Object(patch))
+    })
+}
+
+/// Returns members of value that is an object in JSON.
+fn members(value: &Tree) -> Option<Vec<(&str, &Tree)>> {
+    match value {
+        Tree::Some(value) | Tree::Newtype(value) => members(value),
+        Tree::Struct(entries) => Some(entries.iter().map(|(k, v)| (&**k, v)).collect()),
+        Tree::Map(entries) => entries
+            .iter()
+            .map(|(k, v)| match k {
+                Tree::String(k) => Some((&**k, v)),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+fn apply(target: &mut Tree, patch: &Value) -> Result<(), Error> {
+    let members = match patch {
+        Value::Object(members) => members,
+        _ => {
+            *target = to_value(patch)?;
+            return Ok(());
+        }
+    };
+
+    if let Tree::Declared(_, content) = target {
+        // Declaration may not hold for patched content.
+        *target = core::mem::replace(&mut **content, Tree::Unit);
+    }
+
+    match target {
+        Tree::Some(target) | Tree::Newtype(target) => apply(target, patch),
+        Tree::Struct(entries) => apply_members(entries, members, |key| key.to_owned()),
+        Tree::Map(entries) if entries.iter().all(|(k, _)| matches!(k, Tree::String(_))) => {
+            apply_members(entries, members, |key| Tree::String(key.to_owned()))
+        }
+        _ => {
+            // Patch over non-object is applied to empty object.
+            *target = Tree::Map(Vec::new());
+            apply(target, patch)
+        }
+    }
+}
+
+fn apply_members<K>(
+    entries: &mut Vec<(K, Tree)>,
+    patch: &Map<String, Value>,
+    key: impl Fn(&str) -> K,
+) -> Result<(), Error>
+where
+    K: PartialEq,
+{
+    for (name, patch) in patch {
+        let name = key(name);
+        match (entries.iter().position(|(k, _)| *k == name), patch) {
+            // Removed field is filled back from `Nothing`.
+            (Some(idx), Value::Null) => {
+                entries.remove(idx);
+            }
+            (Some(idx), patch) => apply(&mut entries[idx].1, patch)?,
+            (None, Value::Null) => {}
+            (None, patch) => {
+                let mut value = Tree::None;
+                apply(&mut value, patch)?;
+                entries.push((name, value));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_prune_nothing() {
+    use serde_json::json;
+
+    #[derive(serde_derive::Serialize)]
+    struct Limits {
+        min: u32,
+        max: Option<f32>,
+    }
+
+    #[derive(serde_derive::Serialize)]
+    struct Config {
+        name: String,
+        enabled: bool,
+        limits: Limits,
+        levels: [u8; 3],
+        tags: Vec<String>,
+    }
+
+    // Typed nothing values are pruned entirely when array positions are not kept.
+    let config = Config {
+        name: String::new(),
+        enabled: false,
+        limits: Limits { min: 0, max: None },
+        levels: [0, 0, 0],
+        tags: vec![],
+    };
+    assert!(crate::is_nothing(&config));
+    let mut value = serde_json::to_value(&config).unwrap();
+    let mut removed = value.clone();
+    prune_nothing(&mut value);
+    assert_eq!(value, json!({ "levels": [0, 0, 0] }));
+    prune_nothing_with(
+        &mut removed,
+        PruneOptions {
+            keep_array_positions: false,
+            keep_false: false,
+        },
+    );
+    assert_eq!(removed, json!({}));
+
+    // With array positions kept by default, arrays with nothing elements are kept,
+    // whether they are sequences or tuples.
+    let config = Config {
+        tags: vec![String::new()],
+        levels: [0, 3, 0],
+        ..config
+    };
+    assert!(!crate::is_nothing(&config));
+    let mut value = serde_json::to_value(&config).unwrap();
+    let mut removed = value.clone();
+    prune_nothing(&mut value);
+    assert_eq!(value, json!({ "levels": [0, 3, 0], "tags": [""] }));
+
+    prune_nothing_with(
+        &mut removed,
+        PruneOptions {
+            keep_array_positions: false,
+            keep_false: false,
+        },
+    );
+    assert_eq!(removed, json!({ "levels": [3] }));
+
+    let mut value = json!({
+        "a": { "b": { "c": [null, {}] } },
+        "d": [0, -0.0, 1, [0], [0, 2]],
+        "e": false,
+        "f": "x",
+    });
+    let mut kept = value.clone();
+    let mut removed = value.clone();
+
+    prune_nothing(&mut value);
+    assert_eq!(
+        value,
+        json!({
+            "a": { "b": { "c": [null, {}] } },
+            "d": [0, -0.0, 1, [0], [0, 2]],
+            "f": "x",
+        })
+    );
+
+    prune_nothing_with(
+        &mut removed,
+        PruneOptions {
+            keep_array_positions: false,
+            keep_false: false,
+        },
+    );
+    assert_eq!(removed, json!({ "d": [1, [2]], "f": "x" }));
+
+    prune_nothing_with(
+        &mut kept,
+        PruneOptions {
+            keep_array_positions: true,
+            keep_false: true,
+        },
+    );
+    assert_eq!(
+        kept,
+        json!({
+            "a": { "b": { "c": [null, {}] } },
+            "d": [0, -0.0, 1, [0], [0, 2]],
+            "e": false,
+            "f": "x",
+        })
+    );
+
+    let mut root = json!(0);
+    prune_nothing(&mut root);
+    assert_eq!(root, json!(0));
+}
+
+#[test]
+fn test_merge_patch() {
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[derive(Clone, Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    enum Mode {
+        Off,
+        Fixed(u32),
+    }
+
+    #[derive(Clone, Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    struct Limits {
+        min: u32,
+        max: Option<u32>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    struct Service {
+        name: String,
+        limits: Limits,
+        parent: Option<Limits>,
+        labels: BTreeMap<String, String>,
+        mode: Mode,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+    }
+
+    let old = Service {
+        name: "web".into(),
+        limits: Limits {
+            min: 1,
+            max: Some(10),
+        },
+        parent: None,
+        labels: BTreeMap::from([("a".into(), "1".into()), ("b".into(), "2".into())]),
+        mode: Mode::Fixed(3),
+        tags: vec!["x".into()],
+    };
+
+    assert_eq!(merge_patch(&old, &old).unwrap(), json!({}));
+
+    let mut new = old.clone();
+    new.limits.min = 0;
+    let patch = merge_patch(&old, &new).unwrap();
+    assert_eq!(patch, json!({ "limits": { "min": null } }));
+    let mut patched = old.clone();
+    apply_merge_patch(&mut patched, &patch).unwrap();
+    assert_eq!(patched, new);
+
+    let new = Service {
+        name: "web".into(),
+        limits: Limits { min: 0, max: None },
+        parent: Some(Limits { min: 2, max: None }),
+        labels: BTreeMap::from([("b".into(), "3".into()), ("c".into(), "4".into())]),
+        mode: Mode::Off,
+        tags: vec![],
+    };
+
+    let patch = merge_patch(&old, &new).unwrap();
+    assert_eq!(
+        patch,
+        json!({
+            "limits": null,
+            "parent": { "min": 2, "max": null },
+            "labels": { "a": null, "b": "3", "c": "4" },
+            "mode": null,
+            "tags": null,
+        })
+    );
+
+    let mut patched = old.clone();
+    apply_merge_patch(&mut patched, &patch).unwrap();
+    assert_eq!(patched, new);
+
+    let mut patched = new.clone();
+    apply_merge_patch(&mut patched, &merge_patch(&new, &old).unwrap()).unwrap();
+    assert_eq!(patched, old);
+
+    apply_merge_patch(&mut patched, &json!(null)).unwrap();
+    assert!(crate::is_nothing(&patched));
+}
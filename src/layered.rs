@@ -0,0 +1,326 @@
+//!
+//! Merging values layer over layer.
+//!
+//! Values are merged leaf by leaf:
+//! nothing parts of the overlay keep the base,
+//! any other part of the overlay wins.
+//! Structs, maps, options, newtypes, tuples of the same length and same enum variants
+//! are merged recursively, sequences and other leaves are replaced as a whole.
+//! `Some` is never nothing, so `Some` with nothing inside replaces the base.
+//!
+//! This gives "defaults < file < env < command line" layering
+//! for any `Serialize + Deserialize` type without merge code per type
+//! and without making every field an `Option`.
+//!
+
+use core::{fmt, marker::PhantomData};
+use std::{string::String, string::ToString, vec::Vec};
+
+use serde::{
+    de::{DeserializeOwned, IntoDeserializer},
+    Serialize,
+};
+
+use crate::{
+    value::{to_value, Value},
+    Nothing,
+};
+
+/// Error of [`merge`] and [`Layered`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeError(String);
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+impl serde::ser::Error for MergeError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        MergeError(msg.to_string())
+    }
+}
+
+impl serde::de::Error for MergeError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        MergeError(msg.to_string())
+    }
+}
+
+/// Merges `overlay` over `base`.
+///
+/// ```
+/// #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+/// struct Config {
+///     host: String,
+///     port: u16,
+///     verbose: bool,
+/// }
+///
+/// let base = Config { host: "localhost".into(), port: 80, verbose: false };
+/// let overlay = Config { host: String::new(), port: 8080, verbose: true };
+///
+/// let config = serde_nothing::merge(&base, &overlay).unwrap();
+/// assert_eq!(config, Config { host: "localhost".into(), port: 8080, verbose: true });
+/// ```
+pub fn merge<T>(base: &T, overlay: &T) -> Result<T, MergeError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let mut value = to_value(base)?;
+    merge_value(&mut value, to_value(overlay)?);
+    T::deserialize(value.into_deserializer())
+}
+
+/// Builder that merges any number of layers.
+///
+/// Layers may be of any `Serialize` type, not only `T`,
+/// as long as they look the same in `serde` data model.
+/// For example a `serde_json::Value` read from a file with only some fields present.
+///
+/// ```
+/// # use serde_nothing::layered::Layered;
+/// #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+/// struct Config {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let defaults = Config { host: "localhost".into(), port: 80 };
+/// let file = serde_json::json!({ "port": 8080 });
+/// let cli = Config { host: "example.com".into(), port: 0 };
+///
+/// let config: Config = Layered::new()
+///     .layer(&defaults).unwrap()
+///     .layer(&file).unwrap()
+///     .layer(&cli).unwrap()
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(config, Config { host: "example.com".into(), port: 8080 });
+/// ```
+pub struct Layered<T> {
+    value: Option<Value>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for Layered<T> {
+    fn default() -> Self {
+        Layered::new()
+    }
+}
+
+impl<T> fmt::Debug for Layered<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Layered")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<T> Layered<T> {
+    /// Returns builder without layers.
+    pub fn new() -> Self {
+        Layered {
+            value: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Merges next layer over all previous ones.
+    pub fn layer<L>(&mut self, layer: &L) -> Result<&mut Self, MergeError>
+    where
+        L: ?Sized + Serialize,
+    {
+        let overlay = to_value(layer)?;
+        match &mut self.value {
+            None => self.value = Some(overlay),
+            Some(value) => merge_value(value, overlay),
+        }
+        Ok(self)
+    }
+
+    /// Builds merged value.
+    /// Without layers nothing value is built.
+    pub fn build(&self) -> Result<T, MergeError>
+    where
+        T: DeserializeOwned,
+    {
+        match &self.value {
+            None => T::deserialize(Nothing).map_err(serde::de::Error::custom),
+            Some(value) => T::deserialize(value.clone().into_deserializer()),
+        }
+    }
+}
+
+fn merge_value(base: &mut Value, overlay: Value) {
+    if overlay.is_nothing() {
+        return;
+    }
+
+    match (base, overlay) {
+        (Value::Struct(base), Value::Struct(overlay)) => merge_entries(base, overlay),
+        (Value::Map(base), Value::Map(overlay)) => merge_entries(base, overlay),
+        (Value::Map(base), Value::Struct(overlay)) => merge_entries(
+            base,
+            overlay
+                .into_iter()
+                .map(|(key, value)| (Value::String(key), value))
+                .collect(),
+        ),
+        (Value::Struct(base), Value::Map(overlay))
+            if overlay
+                .iter()
+                .all(|(key, _)| matches!(key, Value::String(_))) =>
+        {
+            merge_entries(
+                base,
+                overlay
+                    .into_iter()
+                    .filter_map(|(key, value)| match key {
+                        Value::String(key) => Some((key, value)),
+                        _ => None,
+                    })
+                    .collect(),
+            )
+        }
+        (Value::Some(base), Value::Some(overlay)) if !overlay.is_nothing() => {
+            merge_value(base, *overlay)
+        }
+        (Value::Tuple(base), Value::Tuple(overlay)) if base.len() == overlay.len() => {
+            for (base, overlay) in base.iter_mut().zip(overlay) {
+                merge_value(base, overlay);
+            }
+        }
+        (Value::Newtype(base), Value::Newtype(overlay)) => merge_value(base, *overlay),
+        (Value::Variant(name, base), Value::Variant(overlay_name, overlay))
+            if *name == overlay_name =>
+        {
+            merge_value(base, *overlay)
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn merge_entries<K>(base: &mut Vec<(K, Value)>, overlay: Vec<(K, Value)>)
+where
+    K: PartialEq,
+{
+    for (key, value) in overlay {
+        match base.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, slot)) => merge_value(slot, value),
+            None => base.push((key, value)),
+        }
+    }
+}
+
+#[test]
+fn test_merge() {
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    enum Mode {
+        Off,
+        Range { min: u32, max: u32 },
+        Fixed(u32),
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    struct Config {
+        name: String,
+        port: u16,
+        tags: Vec<String>,
+        limits: BTreeMap<String, u32>,
+        parent: Option<(u32, u32)>,
+        retries: Option<u32>,
+        point: (u32, u32),
+        mode: Mode,
+    }
+
+    let base = Config {
+        name: "base".into(),
+        port: 80,
+        tags: vec!["a".into(), "b".into()],
+        limits: BTreeMap::from([("cpu".into(), 1), ("mem".into(), 2)]),
+        parent: Some((1, 2)),
+        retries: Some(5),
+        point: (1, 2),
+        mode: Mode::Range { min: 1, max: 10 },
+    };
+
+    let overlay = Config {
+        name: String::new(),
+        port: 8080,
+        tags: vec!["c".into()],
+        limits: BTreeMap::from([("mem".into(), 0), ("disk".into(), 3)]),
+        parent: None,
+        retries: None,
+        point: (0, 7),
+        mode: Mode::Range { min: 0, max: 20 },
+    };
+
+    let merged = merge(&base, &overlay).unwrap();
+    assert_eq!(
+        merged,
+        Config {
+            name: "base".into(),
+            port: 8080,
+            tags: vec!["c".into()],
+            limits: BTreeMap::from([("cpu".into(), 1), ("mem".into(), 2), ("disk".into(), 3)]),
+            parent: Some((1, 2)),
+            retries: Some(5),
+            point: (1, 7),
+            mode: Mode::Range { min: 1, max: 20 },
+        }
+    );
+
+    // Explicit `Some` is not nothing, even with nothing inside.
+    let overlay = Config {
+        parent: Some((0, 7)),
+        retries: Some(0),
+        ..overlay
+    };
+    let zeroed = merge(&base, &overlay).unwrap();
+    assert_eq!(zeroed.parent, Some((1, 7)));
+    assert_eq!(zeroed.retries, Some(0));
+
+    let overlay = Config {
+        mode: Mode::Fixed(5),
+        ..merged
+    };
+    let merged = merge(&base, &overlay).unwrap();
+    assert_eq!(merged.mode, Mode::Fixed(5));
+
+    // Nothing variant does not replace the base.
+    let overlay = Config {
+        mode: Mode::Off,
+        ..merged
+    };
+    assert_eq!(merge(&base, &overlay).unwrap().mode, base.mode);
+
+    let layered = Layered::<Config>::new()
+        .layer(&base)
+        .unwrap()
+        .layer(&serde_json::json!({"name": "file", "limits": {"cpu": 4}}))
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(layered.name, "file");
+    assert_eq!(layered.port, 80);
+    assert_eq!(layered.limits["cpu"], 4);
+    assert_eq!(layered.limits["mem"], 2);
+
+    assert_eq!(
+        Layered::<(u32, String)>::new().build().unwrap(),
+        (0, String::new())
+    );
+}
@@ -0,0 +1,406 @@
+//!
+//! Lenient deserialization helpers.
+//!
+//! Functions in this module are meant for `#[serde(deserialize_with = "...")]`
+//! on fields which peers fill with placeholders like `null` or `""`
+//! instead of leaving them out.
+//! Such placeholders are deserialized as nothing value of the field type,
+//! which requires no `Default` bound.
+//!
+//! `serde_derive` does not infer bounds for fields with `deserialize_with`,
+//! generic structs need `#[serde(bound = "T: Deserialize<'de>")]`.
+//!
+//! [`or_nothing`] goes further and replaces any malformed value with nothing value.
+//! Recovered errors are reported to the innermost [`capture`] on the current thread.
+//!
+
+use core::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{Error, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::Nothing;
+
+/// Deserializes nothing value if input is `null`
+/// and deserializes `T` otherwise.
+///
+/// Input is requested as an option,
+/// so non-self-describing formats expect optional value on the wire.
+///
+/// ```
+/// #[derive(serde_derive::Deserialize)]
+/// #[serde(bound = "T: serde::Deserialize<'de>")]
+/// struct Form<T> {
+///     #[serde(deserialize_with = "serde_nothing::lenient::null_as_nothing")]
+///     count: T,
+/// }
+///
+/// let form: Form<u32> = serde_json::from_str(r#"{"count": null}"#).unwrap();
+/// assert_eq!(form.count, 0);
+///
+/// let form: Form<u32> = serde_json::from_str(r#"{"count": 5}"#).unwrap();
+/// assert_eq!(form.count, 5);
+/// ```
+pub fn null_as_nothing<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    deserializer.deserialize_option(NullVisitor(PhantomData))
+}
+
+struct NullVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for NullVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("value or null")
+    }
+
+    #[inline]
+    fn visit_none<E>(self) -> Result<T, E>
+    where
+        E: Error,
+    {
+        T::deserialize(Nothing).map_err(E::custom)
+    }
+
+    #[inline]
+    fn visit_unit<E>(self) -> Result<T, E>
+    where
+        E: Error,
+    {
+        T::deserialize(Nothing).map_err(E::custom)
+    }
+
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+/// Deserializes nothing value if input is `null`, empty string,
+/// empty sequence or empty map and deserializes `T` otherwise.
+///
+/// Input is buffered with `deserialize_any` before `T` is deserialized,
+/// so it works only with self-describing formats
+/// and `T` cannot borrow from the input.
+///
+/// ```
+/// #[derive(serde_derive::Deserialize)]
+/// #[serde(bound = "T: serde::Deserialize<'de>")]
+/// struct Form<T> {
+///     #[serde(deserialize_with = "serde_nothing::lenient::empty_as_nothing")]
+///     count: T,
+/// }
+///
+/// let form: Form<u32> = serde_json::from_str(r#"{"count": ""}"#).unwrap();
+/// assert_eq!(form.count, 0);
+///
+/// let form: Form<Vec<u32>> = serde_json::from_str(r#"{"count": {}}"#).unwrap();
+/// assert!(form.count.is_empty());
+/// ```
+#[cfg(feature = "alloc")]
+pub fn empty_as_nothing<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    use crate::value::Value;
+    use serde::de::IntoDeserializer;
+
+    let value = Value::deserialize(deserializer)?;
+    match &value {
+        Value::None | Value::Unit => {}
+        Value::String(s) if s.is_empty() => {}
+        Value::Seq(s) if s.is_empty() => {}
+        Value::Map(m) if m.is_empty() => {}
+        _ => return T::deserialize(value.into_deserializer()),
+    }
+    T::deserialize(Nothing).map_err(D::Error::custom)
+}
+
+/// Deserializes `T` and falls back to nothing value if that fails.
+///
+/// Wrong types, unknown enum variants, out-of-range numbers
+/// and any other error of the value itself are recovered from.
+/// Input is buffered with `deserialize_any` first,
+/// so the rest of the document is read as usual,
+/// but it works only with self-describing formats
+/// and `T` cannot borrow from the input.
+/// Syntax errors of the format are not recovered.
+///
+/// Recovered errors are reported to the innermost [`capture`] call
+/// on the current thread and dropped if there is none.
+/// If nothing value of `T` cannot be created, the original error is returned.
+///
+/// ```
+/// # use serde_nothing::lenient;
+/// #[derive(serde_derive::Deserialize)]
+/// enum Mode {
+///     Fast,
+///     Slow,
+/// }
+///
+/// #[derive(serde_derive::Deserialize)]
+/// struct Config {
+///     #[serde(deserialize_with = "lenient::or_nothing")]
+///     mode: Option<Mode>,
+///     #[serde(deserialize_with = "lenient::or_nothing")]
+///     level: u8,
+/// }
+///
+/// let (config, recovered) = lenient::capture(|| {
+///     serde_json::from_str::<Config>(r#"{"mode": "Turbo", "level": 1000}"#)
+/// });
+/// let config = config.unwrap();
+/// assert!(config.mode.is_none());
+/// assert_eq!(config.level, 0);
+/// assert_eq!(recovered.len(), 2);
+/// ```
+#[cfg(feature = "std")]
+pub fn or_nothing<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    use crate::value::Value;
+    use serde::de::IntoDeserializer;
+
+    let value = Value::deserialize(deserializer)?;
+    let err: D::Error = match T::deserialize(value.into_deserializer()) {
+        Ok(value) => return Ok(value),
+        Err(err) => err,
+    };
+
+    match T::deserialize(Nothing) {
+        Ok(value) => {
+            recovered::report(Recovered {
+                type_name: core::any::type_name::<T>(),
+                message: std::string::ToString::to_string(&err),
+            });
+            Ok(value)
+        }
+        Err(_) => Err(err),
+    }
+}
+
+/// Error recovered by [`or_nothing`].
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recovered {
+    /// Name of the type that failed to deserialize.
+    pub type_name: &'static str,
+
+    /// Error message.
+    pub message: std::string::String,
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for Recovered {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "`{}` replaced with nothing: {}",
+            self.type_name, self.message
+        )
+    }
+}
+
+/// Runs `f` and collects errors recovered by [`or_nothing`] on this thread meanwhile.
+///
+/// Nested calls collect their own errors,
+/// which are not reported to outer calls.
+#[cfg(feature = "std")]
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, std::vec::Vec<Recovered>) {
+    recovered::capture(f)
+}
+
+#[cfg(feature = "std")]
+mod recovered {
+    use std::{cell::RefCell, vec::Vec};
+
+    use super::Recovered;
+
+    std::thread_local! {
+        static RECOVERED: RefCell<Option<Vec<Recovered>>> = const { RefCell::new(None) };
+    }
+
+    /// Puts outer collector back, even on panic.
+    struct Restore(Option<Vec<Recovered>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let outer = self.0.take();
+            RECOVERED.with(|r| *r.borrow_mut() = outer);
+        }
+    }
+
+    pub(super) fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<Recovered>) {
+        let restore = Restore(RECOVERED.with(|r| r.replace(Some(Vec::new()))));
+        let result = f();
+        let errors = RECOVERED
+            .with(|r| r.borrow_mut().take())
+            .unwrap_or_default();
+        drop(restore);
+        (result, errors)
+    }
+
+    pub(super) fn report(error: Recovered) {
+        RECOVERED.with(|r| {
+            if let Some(errors) = &mut *r.borrow_mut() {
+                errors.push(error);
+            }
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_lenient() {
+    use std::{collections::BTreeMap, string::String, vec::Vec};
+
+    #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+    #[serde(bound = "T: serde::Deserialize<'de>")]
+    struct Null<T> {
+        #[serde(deserialize_with = "null_as_nothing")]
+        value: T,
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+    #[serde(bound = "T: serde::Deserialize<'de>")]
+    struct Empty<T> {
+        #[serde(deserialize_with = "empty_as_nothing")]
+        value: T,
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+    struct Limits {
+        min: u32,
+        max: Option<u32>,
+    }
+
+    fn null<T: serde::de::DeserializeOwned>(json: &str) -> serde_json::Result<T> {
+        serde_json::from_str::<Null<T>>(json).map(|v| v.value)
+    }
+
+    fn empty<T: serde::de::DeserializeOwned>(json: &str) -> serde_json::Result<T> {
+        serde_json::from_str::<Empty<T>>(json).map(|v| v.value)
+    }
+
+    assert_eq!(null::<u32>(r#"{"value": null}"#).unwrap(), 0);
+    assert_eq!(null::<u32>(r#"{"value": 3}"#).unwrap(), 3);
+    assert_eq!(null::<String>(r#"{"value": null}"#).unwrap(), "");
+    assert_eq!(null::<Option<u32>>(r#"{"value": null}"#).unwrap(), None);
+    assert!(null::<u32>(r#"{"value": ""}"#).is_err());
+
+    assert_eq!(empty::<u32>(r#"{"value": null}"#).unwrap(), 0);
+    assert_eq!(empty::<u32>(r#"{"value": ""}"#).unwrap(), 0);
+    assert_eq!(empty::<u32>(r#"{"value": 3}"#).unwrap(), 3);
+    assert_eq!(
+        empty::<Vec<u32>>(r#"{"value": {}}"#).unwrap(),
+        Vec::<u32>::new()
+    );
+    assert_eq!(
+        empty::<BTreeMap<String, u32>>(r#"{"value": []}"#).unwrap(),
+        BTreeMap::new()
+    );
+    assert_eq!(
+        empty::<Limits>(r#"{"value": {}}"#).unwrap(),
+        Limits { min: 0, max: None }
+    );
+    assert_eq!(
+        empty::<Limits>(r#"{"value": {"min": 1, "max": 2}}"#).unwrap(),
+        Limits {
+            min: 1,
+            max: Some(2)
+        }
+    );
+    assert!(empty::<u32>(r#"{"value": "x"}"#).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_or_nothing() {
+    use std::{string::String, vec::Vec};
+
+    #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+    enum Mode {
+        Fast,
+        Slow,
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+    struct Config {
+        #[serde(deserialize_with = "or_nothing")]
+        mode: Option<Mode>,
+        #[serde(deserialize_with = "or_nothing")]
+        level: u8,
+        #[serde(deserialize_with = "or_nothing")]
+        tags: Vec<String>,
+        #[serde(deserialize_with = "or_nothing")]
+        strict: core::num::NonZeroU8,
+    }
+
+    let (config, recovered) = capture(|| {
+        serde_json::from_str::<Config>(
+            r#"{"mode": "Slow", "level": 3, "tags": ["a"], "strict": 1}"#,
+        )
+    });
+    assert_eq!(
+        config.unwrap(),
+        Config {
+            mode: Some(Mode::Slow),
+            level: 3,
+            tags: vec!["a".into()],
+            strict: core::num::NonZeroU8::new(1).unwrap(),
+        }
+    );
+    assert!(recovered.is_empty());
+
+    let (config, recovered) = capture(|| {
+        serde_json::from_str::<Config>(
+            r#"{"mode": "Turbo", "level": 300, "tags": {"a": 1}, "strict": 1}"#,
+        )
+    });
+    assert_eq!(
+        config.unwrap(),
+        Config {
+            mode: None,
+            level: 0,
+            tags: Vec::new(),
+            strict: core::num::NonZeroU8::new(1).unwrap(),
+        }
+    );
+    assert_eq!(recovered.len(), 3);
+    assert!(recovered[0].message.contains("Turbo"));
+    assert_eq!(recovered[1].type_name, "u8");
+
+    // `NonZeroU8` has no nothing value.
+    let (config, recovered) = capture(|| {
+        serde_json::from_str::<Config>(r#"{"mode": null, "level": 0, "tags": [], "strict": 0}"#)
+    });
+    assert!(config.unwrap_err().to_string().contains("nonzero"));
+    assert!(recovered.is_empty());
+
+    // Nested capture does not leak to the outer one.
+    let ((_, inner), outer) = capture(|| {
+        let inner = capture(|| {
+            serde_json::from_str::<Config>(r#"{"mode": 1, "level": 0, "tags": [], "strict": 1}"#)
+        });
+        let _ =
+            serde_json::from_str::<Config>(r#"{"mode": 1, "level": "x", "tags": [], "strict": 1}"#);
+        inner
+    });
+    assert_eq!(inner.len(), 1);
+    assert_eq!(outer.len(), 2);
+}
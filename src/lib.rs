@@ -42,19 +42,120 @@
 //!
 //! User would most probably want to use a shortcut and utilize `is_nothing` function for serialization
 //! and `from_nothing` function for deserialization.
+//! `IsNothingExt` and `FromNothingExt` traits provide them as `value.is_nothing()` and `T::nothing()`.
+//! With both `IsNothingExt` and `IsNothing` in scope, call `is_nothing` through the trait path
+//! for types that implement both.
+//!
+//! # Fast path
+//!
+//! `is_nothing` runs the whole `Serialize` implementation of the value.
+//! For hot code `IsNothing` trait checks values directly.
+//! With `derive` feature it can be derived for structs and enums,
+//! field types without `IsNothing` implementation are checked with `is_nothing` function.
+//! `BySerde` wrapper falls back to `is_nothing` function for any `Serialize` type.
 //!
 
 #![cfg_attr(not(feature = "std"), no_std)]
+// `?Sized` is kept next to the parameter, same as in `serde` trait declarations.
+#![allow(clippy::multiple_bound_locations)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(test)]
+extern crate self as serde_nothing;
 
 mod de;
+mod ext;
+mod input;
+mod is_nothing;
+mod maybe;
+mod non_nothing;
 mod ser;
+mod something;
+
+pub mod as_null;
+pub mod lenient;
+pub mod or_nothing;
+pub mod protocol;
+pub mod registry;
+
+#[cfg(feature = "alloc")]
+pub mod value;
+
+#[cfg(feature = "alloc")]
+pub mod diff;
+
+#[cfg(feature = "serde_json")]
+pub mod json;
+
+#[cfg(feature = "std")]
+pub mod layered;
+
+#[cfg(feature = "std")]
+pub mod overrides;
+
+#[cfg(feature = "std")]
+pub mod shape;
+
+#[cfg(feature = "std")]
+pub mod compat;
+
+#[cfg(feature = "std")]
+pub mod skeleton;
+
+#[cfg(feature = "std")]
+pub mod sparse;
+
+#[cfg(feature = "toml_edit")]
+pub mod toml;
 
-pub use self::{de::NothingDeserializeError, ser::NothingSerializeError};
+#[cfg(feature = "testing")]
+pub mod testing;
+
+pub use self::{
+    de::NothingDeserializeError,
+    ext::{FromNothingExt, IsNothingExt},
+    input::{is_nothing_input, IsNothingSeed, NothingVisitor},
+    is_nothing::{BySerde, IsNothing},
+    maybe::Maybe,
+    non_nothing::{NonNothing, NothingValueError},
+    ser::NothingSerializeError,
+    something::SomethingDeserializeError,
+};
+
+#[cfg(feature = "derive")]
+pub use serde_nothing_derive::IsNothing;
+
+#[cfg(feature = "alloc")]
+pub use self::{
+    diff::{diff, structurally_eq},
+    value::Value,
+};
+
+#[cfg(feature = "std")]
+pub use self::{layered::merge, overrides::from_nothing_with};
+
+#[doc(hidden)]
+pub use self::is_nothing::__private;
 
 /// Serializer to serialize values into and from nothing.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Nothing;
 
+/// Deserializer to create minimal values that are not "nothing".
+///
+/// Counterpart of `Nothing` deserializer.
+/// Visits `true`, `1`, `"a"`, `Some`, sequences and maps with single element
+/// and structs and tuples with all fields created by `Something`.
+/// Enums get first variant, same as with `Nothing`,
+/// so unit variant would make a "nothing" value anyway.
+///
+/// Useful in tests to check that no field is skipped by mistake
+/// and that every field survives a round-trip.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Something;
+
 /// Returns true if the value matches definition of "nothing".
 /// Typically deserializing from `Nothing` would yield same value.
 #[inline]
@@ -75,6 +176,57 @@ where
     T::deserialize(Nothing).ok()
 }
 
+/// Returns minimal value of the type that is not "nothing".
+/// Or none if failed to create one.
+/// See `Something` deserializer.
+#[inline]
+pub fn from_something<'de, T>() -> Option<T>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    T::deserialize(Something).ok()
+}
+
+/// Resets the value to "nothing" in place.
+///
+/// Drives `Deserialize::deserialize_in_place` with `Nothing` deserializer,
+/// so types that support it keep their allocations.
+/// For example `Vec`, `String` and sets are cleared instead of being replaced.
+/// Derived implementations support this with `deserialize_in_place` feature of `serde_derive`,
+/// otherwise they replace the value as a whole.
+///
+/// On error the value may be left partially reset.
+#[inline]
+pub fn reset_to_nothing<'de, T>(place: &mut T) -> Result<(), NothingDeserializeError>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    T::deserialize_in_place(Nothing, place)
+}
+
+/// Returns some "nothing" value produced by the seed.
+/// Or none if failed to create one.
+#[inline]
+pub fn from_nothing_seed<'de, S>(seed: S) -> Option<S::Value>
+where
+    S: serde::de::DeserializeSeed<'de>,
+{
+    seed.deserialize(Nothing).ok()
+}
+
+/// Returns some "nothing" value of the type.
+/// Or none if failed to create one.
+///
+/// Same as `from_nothing` but without free `'de` lifetime,
+/// which is easier to use in generic code.
+#[inline]
+pub fn from_nothing_owned<T>() -> Option<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    T::deserialize(Nothing).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use core::fmt::Debug;
@@ -90,6 +242,7 @@ mod tests {
     }
 
     #[cfg(test)]
+    #[allow(clippy::needless_maybe_sized)]
     fn check_roundtrip<'de, T: ?Sized + Serialize + Deserialize<'de> + PartialEq + Debug>(
         value: &T,
     ) {
@@ -174,4 +327,54 @@ mod tests {
             string: "",
         })
     }
+
+    #[test]
+    fn test_seed() {
+        struct Offset(u32);
+
+        impl<'de> serde::de::DeserializeSeed<'de> for Offset {
+            type Value = u32;
+
+            fn deserialize<D>(self, deserializer: D) -> Result<u32, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                u32::deserialize(deserializer).map(|v| v + self.0)
+            }
+        }
+
+        assert_eq!(crate::from_nothing_seed(Offset(5)), Some(5));
+        assert_eq!(crate::from_nothing_owned::<(u32, bool)>(), Some((0, false)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reset() {
+        #[derive(serde_derive::Deserialize)]
+        struct Buffers {
+            name: String,
+            data: Vec<u8>,
+            chunks: [Vec<u32>; 2],
+        }
+
+        let mut buffers = Buffers {
+            name: "name".into(),
+            data: vec![1, 2, 3],
+            chunks: [vec![1], vec![2, 3]],
+        };
+
+        let name = buffers.name.as_ptr();
+        let data = buffers.data.as_ptr();
+        let chunk = buffers.chunks[1].as_ptr();
+
+        crate::reset_to_nothing(&mut buffers).unwrap();
+
+        assert!(buffers.name.is_empty());
+        assert!(buffers.data.is_empty());
+        assert!(buffers.chunks.iter().all(Vec::is_empty));
+
+        assert_eq!(buffers.name.as_ptr(), name);
+        assert_eq!(buffers.data.as_ptr(), data);
+        assert_eq!(buffers.chunks[1].as_ptr(), chunk);
+    }
 }
@@ -0,0 +1,202 @@
+use core::{fmt, marker::PhantomData};
+
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{is_nothing, reset_to_nothing, NothingDeserializeError};
+
+/// Field that tells apart missing value, explicit nothing and a value.
+///
+/// Made for partial updates, where missing field means "keep",
+/// `null` or any other nothing value means "clear"
+/// and anything else means "set".
+///
+/// Use with `#[serde(default, skip_serializing_if = "Maybe::is_absent")]`.
+/// Missing field becomes `Absent` and `Absent` is not written.
+/// Input is classified with `is_nothing`, so `null`, `0` and `""` are all `Nothing`.
+/// `Nothing` is written as `null`.
+///
+/// ```
+/// # use serde_nothing::Maybe;
+/// #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+/// struct Patch {
+///     #[serde(default, skip_serializing_if = "Maybe::is_absent")]
+///     name: Maybe<String>,
+///     #[serde(default, skip_serializing_if = "Maybe::is_absent")]
+///     replicas: Maybe<u32>,
+/// }
+///
+/// let patch: Patch = serde_json::from_str(r#"{"name": null}"#).unwrap();
+/// assert_eq!(patch.name, Maybe::Nothing);
+/// assert_eq!(patch.replicas, Maybe::Absent);
+///
+/// assert_eq!(serde_json::to_string(&patch).unwrap(), r#"{"name":null}"#);
+///
+/// let mut name = String::from("web");
+/// patch.name.apply_to(&mut name).unwrap();
+/// assert_eq!(name, "");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Maybe<T> {
+    /// Value is missing.
+    Absent,
+
+    /// Value is nothing.
+    Nothing,
+
+    /// Value is not nothing.
+    Value(T),
+}
+
+impl<T> Default for Maybe<T> {
+    #[inline]
+    fn default() -> Self {
+        Maybe::Absent
+    }
+}
+
+impl<T> Maybe<T> {
+    /// Returns true if value is missing.
+    #[inline]
+    pub fn is_absent(&self) -> bool {
+        matches!(self, Maybe::Absent)
+    }
+
+    /// Returns reference to the value if there is one.
+    #[inline]
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Maybe::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Applies this to the target.
+    ///
+    /// `Absent` keeps target intact,
+    /// `Nothing` resets it to nothing with [`reset_to_nothing`]
+    /// and `Value` replaces it.
+    #[inline]
+    pub fn apply_to<'de>(self, target: &mut T) -> Result<(), NothingDeserializeError>
+    where
+        T: Deserialize<'de>,
+    {
+        match self {
+            Maybe::Absent => Ok(()),
+            Maybe::Nothing => reset_to_nothing(target),
+            Maybe::Value(value) => {
+                *target = value;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T> Serialize for Maybe<T>
+where
+    T: Serialize,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Maybe::Absent | Maybe::Nothing => serializer.serialize_none(),
+            Maybe::Value(value) => serializer.serialize_some(value),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Maybe<T>
+where
+    T: Serialize + Deserialize<'de>,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_option(MaybeVisitor(PhantomData))
+    }
+}
+
+struct MaybeVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for MaybeVisitor<T>
+where
+    T: Serialize + Deserialize<'de>,
+{
+    type Value = Maybe<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("value or null")
+    }
+
+    #[inline]
+    fn visit_none<E>(self) -> Result<Maybe<T>, E> {
+        Ok(Maybe::Nothing)
+    }
+
+    #[inline]
+    fn visit_unit<E>(self) -> Result<Maybe<T>, E> {
+        Ok(Maybe::Nothing)
+    }
+
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<Maybe<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = T::deserialize(deserializer)?;
+        if is_nothing(&value) {
+            Ok(Maybe::Nothing)
+        } else {
+            Ok(Maybe::Value(value))
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_maybe() {
+    use std::{string::String, vec::Vec};
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    struct Patch {
+        #[serde(default, skip_serializing_if = "Maybe::is_absent")]
+        name: Maybe<String>,
+        #[serde(default, skip_serializing_if = "Maybe::is_absent")]
+        replicas: Maybe<u32>,
+        #[serde(default, skip_serializing_if = "Maybe::is_absent")]
+        tags: Maybe<Vec<String>>,
+    }
+
+    let patch: Patch = serde_json::from_str(r#"{"name": "", "replicas": 3}"#).unwrap();
+    assert_eq!(
+        patch,
+        Patch {
+            name: Maybe::Nothing,
+            replicas: Maybe::Value(3),
+            tags: Maybe::Absent,
+        }
+    );
+    assert_eq!(
+        serde_json::to_string(&patch).unwrap(),
+        r#"{"name":null,"replicas":3}"#
+    );
+
+    let patch: Patch = serde_json::from_str(r#"{"replicas": 0, "tags": ["a"]}"#).unwrap();
+    assert_eq!(patch.name, Maybe::Absent);
+    assert_eq!(patch.replicas, Maybe::Nothing);
+    assert_eq!(patch.tags.value().map(Vec::len), Some(1));
+
+    let mut name = String::from("web");
+    let mut replicas = 5;
+    let mut tags = vec![String::from("b")];
+    patch.name.apply_to(&mut name).unwrap();
+    patch.replicas.apply_to(&mut replicas).unwrap();
+    patch.tags.apply_to(&mut tags).unwrap();
+    assert_eq!(name, "web");
+    assert_eq!(replicas, 0);
+    assert_eq!(tags, ["a"]);
+}
@@ -0,0 +1,204 @@
+use core::{convert::TryFrom, fmt, ops::Deref};
+
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::is_nothing;
+
+/// Wrapper for values that are never "nothing".
+///
+/// Deserialization fails if deserialized value is nothing
+/// and serialization fails for nothing values that got in with interior mutability.
+/// Useful for required fields, like names that must not be empty
+/// or counts that must not be zero.
+///
+/// ```
+/// # use serde_nothing::NonNothing;
+/// # use core::convert::TryFrom;
+/// #[derive(serde_derive::Deserialize)]
+/// struct Deployment {
+///     name: NonNothing<String>,
+///     replicas: NonNothing<u32>,
+/// }
+///
+/// let deployment: Deployment = serde_json::from_str(r#"{"name": "web", "replicas": 3}"#).unwrap();
+/// assert_eq!(*deployment.replicas, 3);
+///
+/// let err = serde_json::from_str::<Deployment>(r#"{"name": "web", "replicas": 0}"#).err().unwrap();
+/// assert!(err.to_string().contains("`u32`"));
+///
+/// assert!(NonNothing::try_from("").is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NonNothing<T>(T);
+
+impl<T> NonNothing<T>
+where
+    T: Serialize,
+{
+    /// Wraps the value.
+    /// Returns error if the value is nothing.
+    #[inline]
+    pub fn new(value: T) -> Result<Self, NothingValueError> {
+        if is_nothing(&value) {
+            Err(NothingValueError::of::<T>())
+        } else {
+            Ok(NonNothing(value))
+        }
+    }
+}
+
+impl<T> NonNothing<T> {
+    /// Returns wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for NonNothing<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> AsRef<T> for NonNothing<T> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+// Generic `impl<T> TryFrom<T> for NonNothing<T>` conflicts with blanket impl in `core`,
+// `NonNothing::new` is generic counterpart.
+macro_rules! impl_try_from {
+    ($({$($param:tt),*})? $ty:ty) => {
+        impl$(<$($param),*>)? TryFrom<$ty> for NonNothing<$ty>
+        where
+            $ty: Serialize,
+        {
+            type Error = NothingValueError;
+
+            #[inline]
+            fn try_from(value: $ty) -> Result<Self, NothingValueError> {
+                NonNothing::new(value)
+            }
+        }
+    };
+}
+
+impl_try_from!(bool);
+impl_try_from!(char);
+impl_try_from!(u8);
+impl_try_from!(u16);
+impl_try_from!(u32);
+impl_try_from!(u64);
+impl_try_from!(u128);
+impl_try_from!(usize);
+impl_try_from!(i8);
+impl_try_from!(i16);
+impl_try_from!(i32);
+impl_try_from!(i64);
+impl_try_from!(i128);
+impl_try_from!(isize);
+impl_try_from!(f32);
+impl_try_from!(f64);
+impl_try_from!({'a} &'a str);
+impl_try_from!({'a, T} &'a [T]);
+impl_try_from!({T} Option<T>);
+
+#[cfg(feature = "alloc")]
+impl_try_from!(alloc::string::String);
+
+#[cfg(feature = "alloc")]
+impl_try_from!({T} alloc::vec::Vec<T>);
+
+impl<T> Serialize for NonNothing<T>
+where
+    T: Serialize,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if is_nothing(&self.0) {
+            return Err(ser::Error::custom(NothingValueError::of::<T>()));
+        }
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for NonNothing<T>
+where
+    T: Serialize + Deserialize<'de>,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = T::deserialize(deserializer)?;
+        NonNothing::new(value).map_err(de::Error::custom)
+    }
+}
+
+/// Error returned when `NonNothing` gets nothing value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NothingValueError {
+    type_name: &'static str,
+}
+
+impl NothingValueError {
+    fn of<T>() -> Self {
+        NothingValueError {
+            type_name: core::any::type_name::<T>(),
+        }
+    }
+
+    /// Returns name of the type which value was nothing.
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+impl fmt::Display for NothingValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "value of type `{}` must not be nothing", self.type_name)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NothingValueError {}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_non_nothing() {
+    use core::cell::Cell;
+
+    let value = NonNothing::new(5u32).unwrap();
+    assert_eq!(*value, 5);
+    assert_eq!(value.into_inner(), 5);
+
+    let err = NonNothing::try_from(0u32).unwrap_err();
+    assert_eq!(err.type_name(), "u32");
+    assert!(NonNothing::try_from("").is_err());
+    assert!(NonNothing::try_from(Some(0u32)).is_ok());
+    assert!(NonNothing::try_from(None::<u32>).is_err());
+
+    assert_eq!(
+        serde_json::from_str::<NonNothing<u32>>("3").unwrap(),
+        NonNothing::new(3).unwrap()
+    );
+    let err = serde_json::from_str::<NonNothing<(u32, bool)>>("[0, false]").unwrap_err();
+    assert!(err.to_string().contains("`(u32, bool)`"));
+
+    // Nothing value smuggled in with interior mutability.
+    let value = NonNothing::new(Cell::new(1u32)).unwrap();
+    assert_eq!(serde_json::to_string(&value).unwrap(), "1");
+    value.set(0);
+    assert!(serde_json::to_string(&value).is_err());
+}
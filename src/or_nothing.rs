@@ -0,0 +1,350 @@
+//!
+//! Empty input as nothing value.
+//!
+//! Empty config file or request body is a valid way to say "all defaults",
+//! but formats fail on it or require every non-default field.
+//! [`OrNothing`] deserializer checks the input before parsing
+//! and deserializes from `Nothing` if it is blank.
+//!
+//! With `serde_json` and `toml` features `json` and `toml` submodules
+//! provide shortcuts for those formats.
+//!
+
+use serde::{de::Error, de::Visitor, Deserializer};
+
+use crate::Nothing;
+
+/// Deserializer that deserializes from `Nothing` if input is empty
+/// and from wrapped deserializer otherwise.
+///
+/// ```
+/// # use serde_nothing::or_nothing::OrNothing;
+/// #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+/// struct Config {
+///     name: String,
+///     tags: Vec<String>,
+/// }
+///
+/// let input = " \n";
+/// let mut de = serde_json::Deserializer::from_str(input);
+/// let config: Config = serde::Deserialize::deserialize(OrNothing::from_str(input, &mut de)).unwrap();
+/// assert_eq!(config, Config { name: String::new(), tags: Vec::new() });
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub enum OrNothing<D> {
+    /// Input is empty, values are deserialized from `Nothing`.
+    Empty,
+
+    /// Input is not empty.
+    Input(D),
+}
+
+impl<D> OrNothing<D> {
+    /// Wraps deserializer for the string input.
+    /// Input that contains only whitespace is empty.
+    pub fn from_str(input: &str, de: D) -> Self {
+        if is_blank(input.as_bytes()) {
+            OrNothing::Empty
+        } else {
+            OrNothing::Input(de)
+        }
+    }
+
+    /// Wraps deserializer for the bytes input.
+    /// Input that contains only ASCII whitespace is empty.
+    pub fn from_slice(input: &[u8], de: D) -> Self {
+        if is_blank(input) {
+            OrNothing::Empty
+        } else {
+            OrNothing::Input(de)
+        }
+    }
+
+    /// Borrows wrapped deserializer.
+    /// Useful for formats that implement `Deserializer` for mutable references.
+    pub fn as_mut(&mut self) -> OrNothing<&mut D> {
+        match self {
+            OrNothing::Empty => OrNothing::Empty,
+            OrNothing::Input(de) => OrNothing::Input(de),
+        }
+    }
+
+    /// Creates deserializer for the reader.
+    ///
+    /// Reads until first non-whitespace byte.
+    /// If reader ends before that, input is empty,
+    /// otherwise `f` creates deserializer for the reader
+    /// prefixed with bytes read so far.
+    #[cfg(feature = "std")]
+    pub fn from_reader<R, F>(mut reader: R, f: F) -> std::io::Result<Self>
+    where
+        R: std::io::Read,
+        F: FnOnce(Peeked<R>) -> D,
+    {
+        let mut buf = [0; 256];
+        loop {
+            let len = match reader.read(&mut buf) {
+                Ok(0) => return Ok(OrNothing::Empty),
+                Ok(len) => len,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+
+            if let Some(start) = buf[..len].iter().position(|b| !b.is_ascii_whitespace()) {
+                let head = std::io::Cursor::new(buf[start..len].to_vec());
+                return Ok(OrNothing::Input(f(std::io::Read::chain(head, reader))));
+            }
+        }
+    }
+}
+
+/// Reader with bytes read by [`OrNothing::from_reader`] put back.
+#[cfg(feature = "std")]
+pub type Peeked<R> = std::io::Chain<std::io::Cursor<std::vec::Vec<u8>>, R>;
+
+fn is_blank(input: &[u8]) -> bool {
+    input.iter().all(u8::is_ascii_whitespace)
+}
+
+macro_rules! forward_or_nothing {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            #[inline]
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error>
+            where
+                V: Visitor<'de>,
+            {
+                match self {
+                    OrNothing::Empty => Nothing.$method($($arg,)* visitor).map_err(D::Error::custom),
+                    OrNothing::Input(de) => de.$method($($arg,)* visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de, D> Deserializer<'de> for OrNothing<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward_or_nothing! {
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        match self {
+            OrNothing::Empty => true,
+            OrNothing::Input(de) => de.is_human_readable(),
+        }
+    }
+}
+
+/// Shortcuts for `serde_json` with empty input deserialized from `Nothing`.
+///
+/// Requires `serde_json` feature.
+#[cfg(feature = "serde_json")]
+pub mod json {
+    use serde::{
+        de::{DeserializeOwned, Error},
+        Deserialize,
+    };
+
+    use super::OrNothing;
+    use crate::Nothing;
+
+    /// Deserializes value from JSON string.
+    /// Blank string is deserialized as nothing value.
+    pub fn from_str<'a, T>(input: &'a str) -> serde_json::Result<T>
+    where
+        T: Deserialize<'a>,
+    {
+        match OrNothing::from_str(input, serde_json::Deserializer::from_str(input)) {
+            OrNothing::Empty => T::deserialize(Nothing).map_err(Error::custom),
+            OrNothing::Input(mut de) => {
+                let value = T::deserialize(&mut de)?;
+                de.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    /// Deserializes value from JSON bytes.
+    /// Blank input is deserialized as nothing value.
+    pub fn from_slice<'a, T>(input: &'a [u8]) -> serde_json::Result<T>
+    where
+        T: Deserialize<'a>,
+    {
+        match OrNothing::from_slice(input, serde_json::Deserializer::from_slice(input)) {
+            OrNothing::Empty => T::deserialize(Nothing).map_err(Error::custom),
+            OrNothing::Input(mut de) => {
+                let value = T::deserialize(&mut de)?;
+                de.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    /// Deserializes value from JSON reader.
+    /// Reader without anything but whitespace is deserialized as nothing value.
+    pub fn from_reader<R, T>(reader: R) -> serde_json::Result<T>
+    where
+        R: std::io::Read,
+        T: DeserializeOwned,
+    {
+        let de = OrNothing::from_reader(reader, serde_json::Deserializer::from_reader)
+            .map_err(serde_json::Error::io)?;
+        match de {
+            OrNothing::Empty => T::deserialize(Nothing).map_err(Error::custom),
+            OrNothing::Input(mut de) => {
+                let value = T::deserialize(&mut de)?;
+                de.end()?;
+                Ok(value)
+            }
+        }
+    }
+}
+
+/// Shortcuts for `toml` with empty input deserialized from `Nothing`.
+///
+/// Requires `toml` feature.
+#[cfg(feature = "toml")]
+pub mod toml {
+    use serde::de::{DeserializeOwned, Error};
+
+    use crate::Nothing;
+
+    /// Deserializes value from TOML document.
+    /// Document with nothing but whitespace and comments
+    /// is deserialized as nothing value.
+    pub fn from_str<T>(input: &str) -> Result<T, ::toml::de::Error>
+    where
+        T: DeserializeOwned,
+    {
+        let empty = input.lines().all(|line| {
+            let line = line.trim();
+            line.is_empty() || line.starts_with('#')
+        });
+
+        if empty {
+            T::deserialize(Nothing).map_err(Error::custom)
+        } else {
+            T::deserialize(::toml::Deserializer::new(input))
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_or_nothing() {
+    use serde::Deserialize;
+    use std::{string::String, vec::Vec};
+
+    #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+    struct Config {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    let mut de = serde_json::Deserializer::from_str("");
+    let config = Config::deserialize(OrNothing::from_str("", &mut de)).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            name: String::new(),
+            tags: Vec::new(),
+        }
+    );
+
+    let input = br#" {"name": "a", "tags": ["b"]} "#;
+    let mut de = serde_json::Deserializer::from_slice(input);
+    let config = Config::deserialize(OrNothing::from_slice(input, &mut de)).unwrap();
+    assert_eq!(config.name, "a");
+    assert_eq!(config.tags, ["b"]);
+
+    let mut de = serde_json::Deserializer::from_str("{}");
+    let err = Config::deserialize(OrNothing::from_str("{}", &mut de)).unwrap_err();
+    assert!(err.to_string().contains("missing field"));
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_or_nothing_reader() {
+    use serde::Deserialize;
+    use std::vec::Vec;
+
+    let de =
+        OrNothing::from_reader(&b"  \n\t "[..], serde_json::Deserializer::from_reader).unwrap();
+    assert!(matches!(de, OrNothing::Empty));
+
+    let de =
+        OrNothing::from_reader(&b"  [1, 2]"[..], serde_json::Deserializer::from_reader).unwrap();
+    let mut de = match de {
+        OrNothing::Input(de) => de,
+        OrNothing::Empty => panic!("input is not empty"),
+    };
+    let value = Vec::<u32>::deserialize(&mut de).unwrap();
+    assert_eq!(value, [1, 2]);
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_json() {
+    use std::vec::Vec;
+
+    assert_eq!(json::from_str::<Vec<u32>>(" ").unwrap(), Vec::<u32>::new());
+    assert_eq!(json::from_slice::<Option<u32>>(b"").unwrap(), None);
+    assert_eq!(json::from_reader::<_, u32>(&b"\n"[..]).unwrap(), 0);
+    assert_eq!(json::from_reader::<_, u32>(&b" 5 "[..]).unwrap(), 5);
+    assert!(json::from_str::<u32>("5 x").is_err());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml() {
+    use std::{string::String, vec::Vec};
+
+    #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+    struct Config {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    let config: Config = toml::from_str("# nothing here\n\n").unwrap();
+    assert_eq!(config.name, "");
+
+    let config: Config = toml::from_str("name = \"a\"\ntags = []").unwrap();
+    assert_eq!(config.name, "a");
+}
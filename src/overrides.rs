@@ -0,0 +1,927 @@
+//!
+//! Values built from nothing with some parts overridden.
+//!
+//! [`from_nothing_with`] deserializes a value from `Nothing`
+//! except parts at paths listed in [`Overrides`].
+//! Paths are field names, map keys, sequence and tuple indices and variant names
+//! separated by dots, like `server.ports.0` or `mode.Range.min`.
+//!
+//! Overridden parts are deserialized either from values taken from any `Deserializer`
+//! or from strings, like `--set server.port=8080` command line flags.
+//! Strings are parsed into whatever the part requests:
+//! numbers, booleans, `null` for `None`, comma separated sequences, unit variant names.
+//!
+//! This also builds types that cannot be nothing,
+//! as long as every part that cannot be nothing is overridden.
+//!
+
+use core::{cell::Cell, fmt};
+use std::{format, string::String, string::ToString, vec::Vec};
+
+use serde::{
+    de::{
+        value::{SeqDeserializer, StrDeserializer},
+        DeserializeSeed, EnumAccess, Error, IntoDeserializer, MapAccess, SeqAccess, Unexpected,
+        VariantAccess, Visitor,
+    },
+    Deserialize, Deserializer,
+};
+
+use crate::{registry, value::Value, Nothing, NothingDeserializeError};
+
+/// Error of [`from_nothing_with`] and [`Overrides::parse`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OverrideError {
+    /// Assignment is not in `path=value` form.
+    Syntax(String),
+
+    /// Override path does not match any part of the type.
+    UnknownPath(String),
+
+    /// Value could not be deserialized.
+    Message(String),
+}
+
+impl fmt::Display for OverrideError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OverrideError::Syntax(assignment) => {
+                write!(f, "expected `path=value`, got `{}`", assignment)
+            }
+            OverrideError::UnknownPath(path) => write!(f, "unknown path `{}`", path),
+            OverrideError::Message(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for OverrideError {}
+
+impl Error for OverrideError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        OverrideError::Message(msg.to_string())
+    }
+}
+
+/// Set of path-keyed overrides for [`from_nothing_with`].
+///
+/// Later overrides of the same path replace earlier ones.
+#[derive(Clone, Debug, Default)]
+pub struct Overrides {
+    entries: Vec<Override>,
+}
+
+#[derive(Clone, Debug)]
+struct Override {
+    path: Vec<String>,
+    source: Source,
+}
+
+#[derive(Clone, Debug)]
+enum Source {
+    Value(Value),
+    Str(String),
+}
+
+impl Overrides {
+    /// Returns empty set of overrides.
+    pub fn new() -> Self {
+        Overrides::default()
+    }
+
+    /// Overrides part at `path` with value from the deserializer.
+    ///
+    /// Value is read eagerly with `deserialize_any`,
+    /// so the deserializer must be self-describing.
+    pub fn set<'de, D>(&mut self, path: &str, value: D) -> Result<&mut Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(value)?;
+        self.push(path, Source::Value(value));
+        Ok(self)
+    }
+
+    /// Overrides part at `path` with value parsed from the string.
+    pub fn set_str(&mut self, path: &str, value: &str) -> &mut Self {
+        self.push(path, Source::Str(value.into()));
+        self
+    }
+
+    /// Overrides part with assignment in `path=value` form.
+    pub fn parse(&mut self, assignment: &str) -> Result<&mut Self, OverrideError> {
+        match assignment.find('=') {
+            None => Err(OverrideError::Syntax(assignment.into())),
+            Some(eq) => {
+                let path = assignment[..eq].trim();
+                let value = &assignment[eq + 1..];
+                Ok(self.set_str(path, value))
+            }
+        }
+    }
+
+    fn push(&mut self, path: &str, source: Source) {
+        let path = if path.is_empty() {
+            Vec::new()
+        } else {
+            path.split('.').map(String::from).collect()
+        };
+        self.entries.push(Override { path, source });
+    }
+}
+
+/// Deserializes value from `Nothing` with overridden parts.
+///
+/// Fails if any override path does not match a part of the type.
+///
+/// ```
+/// # use serde_nothing::overrides::Overrides;
+/// #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+/// struct Server {
+///     host: String,
+///     port: std::num::NonZeroU16,
+///     tags: Vec<String>,
+/// }
+///
+/// let mut overrides = Overrides::new();
+/// overrides.parse("port=8080").unwrap();
+/// overrides.parse("tags=a,b").unwrap();
+///
+/// let server: Server = serde_nothing::from_nothing_with(&overrides).unwrap();
+/// assert_eq!(server.host, "");
+/// assert_eq!(server.port.get(), 8080);
+/// assert_eq!(server.tags, ["a", "b"]);
+/// ```
+pub fn from_nothing_with<'de, T>(overrides: &Overrides) -> Result<T, OverrideError>
+where
+    T: Deserialize<'de>,
+{
+    let used: Vec<_> = overrides.entries.iter().map(|_| Cell::new(false)).collect();
+
+    let value = T::deserialize(Filler {
+        entries: &overrides.entries,
+        used: &used,
+        path: Vec::new(),
+    })?;
+
+    match overrides
+        .entries
+        .iter()
+        .zip(&used)
+        .find(|(_, used)| !used.get())
+    {
+        Some((entry, _)) => Err(OverrideError::UnknownPath(entry.path.join("."))),
+        None => Ok(value),
+    }
+}
+
+/// Deserializer of a part at some path.
+struct Filler<'a> {
+    entries: &'a [Override],
+    used: &'a [Cell<bool>],
+    path: Vec<String>,
+}
+
+impl<'a> Filler<'a> {
+    fn child(&self, segment: &str) -> Filler<'a> {
+        let mut path = self.path.clone();
+        path.push(segment.into());
+        Filler {
+            entries: self.entries,
+            used: self.used,
+            path,
+        }
+    }
+
+    /// Returns override for exactly this path.
+    fn exact(&self) -> Option<&'a Source> {
+        let mut found = None;
+        for (entry, used) in self.entries.iter().zip(self.used) {
+            if entry.path == self.path {
+                used.set(true);
+                found = Some(&entry.source);
+            }
+        }
+        found
+    }
+
+    /// Returns next segments of overrides for parts inside this part.
+    fn deeper(&self) -> Vec<&'a str> {
+        let mut segments: Vec<&'a str> = Vec::new();
+        for entry in self.entries {
+            if entry.path.len() > self.path.len() && entry.path.starts_with(&self.path) {
+                let segment = &*entry.path[self.path.len()];
+                if !segments.contains(&segment) {
+                    segments.push(segment);
+                }
+            }
+        }
+        segments
+    }
+
+    /// Returns `true` if there is no override inside this part
+    /// and `Nothing` has a registered rule for the type.
+    /// Otherwise the part is traversed to point errors at the innermost part.
+    fn registered(&self, name: &str) -> bool {
+        self.deeper().is_empty() && registry::lookup(name).is_some()
+    }
+
+    fn display_path(&self) -> String {
+        if self.path.is_empty() {
+            "value".into()
+        } else {
+            self.path.join(".")
+        }
+    }
+
+    fn context(&self, err: OverrideError) -> OverrideError {
+        OverrideError::Message(format!("`{}`: {}", self.display_path(), err))
+    }
+
+    fn nothing<T>(&self, result: Result<T, NothingDeserializeError>) -> Result<T, OverrideError> {
+        result.map_err(|_| {
+            OverrideError::Message(format!(
+                "`{}` cannot be nothing and must be overridden",
+                self.display_path()
+            ))
+        })
+    }
+
+    fn visit_elements<'de, V>(
+        self,
+        names: Vec<String>,
+        visitor: V,
+    ) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(FillSeq {
+            parent: &self,
+            names: names.into_iter(),
+        })
+    }
+}
+
+/// Deserializes from the override for exactly this path, if there is one.
+macro_rules! exact {
+    ($self:ident.$method:ident($($arg:expr),*)) => {
+        if let Some(source) = $self.exact() {
+            let result = match source {
+                Source::Value(value) => {
+                    IntoDeserializer::<OverrideError>::into_deserializer(value.clone())
+                        .$method($($arg),*)
+                }
+                Source::Str(value) => StrValue(value).$method($($arg),*),
+            };
+            return result.map_err(|err| $self.context(err));
+        }
+    };
+}
+
+macro_rules! fill_leaf {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, OverrideError>
+            where
+                V: Visitor<'de>,
+            {
+                exact!(self.$method($($arg,)* visitor));
+                self.nothing(Nothing.$method($($arg,)* visitor))
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Filler<'_> {
+    type Error = OverrideError;
+
+    fill_leaf! {
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_identifier(),
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        exact!(self.deserialize_any(visitor));
+        if self.deeper().is_empty() {
+            return self.nothing(Nothing.deserialize_any(visitor));
+        }
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        exact!(self.deserialize_ignored_any(visitor));
+        if self.deeper().is_empty() {
+            return self.nothing(Nothing.deserialize_ignored_any(visitor));
+        }
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        exact!(self.deserialize_option(visitor));
+        if self.deeper().is_empty() {
+            self.nothing(Nothing.deserialize_option(visitor))
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        exact!(self.deserialize_newtype_struct(name, visitor));
+        if self.registered(name) {
+            return self.nothing(Nothing.deserialize_newtype_struct(name, visitor));
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        exact!(self.deserialize_seq(visitor));
+        let deeper = self.deeper();
+        if deeper.is_empty() {
+            return self.nothing(Nothing.deserialize_seq(visitor));
+        }
+
+        // Non-index segments stay unused and are reported.
+        let len = deeper
+            .iter()
+            .filter_map(|segment| segment.parse::<usize>().ok())
+            .map(|index| index + 1)
+            .max()
+            .unwrap_or(0);
+        self.visit_elements((0..len).map(|index| index.to_string()).collect(), visitor)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        exact!(self.deserialize_tuple(len, visitor));
+        self.visit_elements((0..len).map(|index| index.to_string()).collect(), visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        exact!(self.deserialize_tuple_struct(name, len, visitor));
+        if self.registered(name) {
+            return self.nothing(Nothing.deserialize_tuple_struct(name, len, visitor));
+        }
+        self.visit_elements((0..len).map(|index| index.to_string()).collect(), visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        exact!(self.deserialize_map(visitor));
+        let keys = self.deeper();
+        if keys.is_empty() {
+            return self.nothing(Nothing.deserialize_map(visitor));
+        }
+        visitor.visit_map(FillMap {
+            parent: &self,
+            keys: keys.into_iter(),
+            key: "",
+        })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        exact!(self.deserialize_struct(name, fields, visitor));
+        if self.registered(name) {
+            return self.nothing(Nothing.deserialize_struct(name, fields, visitor));
+        }
+        self.visit_elements(fields.iter().map(|&field| field.into()).collect(), visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        exact!(self.deserialize_enum(name, variants, visitor));
+        if self.registered(name) {
+            return self.nothing(Nothing.deserialize_enum(name, variants, visitor));
+        }
+        let deeper = self.deeper();
+
+        // Variant is selected by path segment, otherwise overrides apply to the first variant.
+        match variants.iter().find(|variant| deeper.contains(&**variant)) {
+            Some(variant) => {
+                let payload = self.child(variant);
+                visitor.visit_enum(FillEnum { variant, payload })
+            }
+            None => match variants.first() {
+                None => self.nothing(Nothing.deserialize_enum(name, variants, visitor)),
+                Some(variant) => visitor.visit_enum(FillEnum {
+                    variant,
+                    payload: self,
+                }),
+            },
+        }
+    }
+}
+
+struct FillSeq<'a, 'b> {
+    parent: &'a Filler<'b>,
+    names: std::vec::IntoIter<String>,
+}
+
+impl<'de> SeqAccess<'de> for FillSeq<'_, '_> {
+    type Error = OverrideError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, OverrideError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.names.next() {
+            None => Ok(None),
+            Some(name) => seed.deserialize(self.parent.child(&name)).map(Some),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.names.len())
+    }
+}
+
+struct FillMap<'a, 'b> {
+    parent: &'a Filler<'b>,
+    keys: std::vec::IntoIter<&'b str>,
+    key: &'b str,
+}
+
+impl<'de> MapAccess<'de> for FillMap<'_, '_> {
+    type Error = OverrideError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, OverrideError>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.keys.next() {
+            None => Ok(None),
+            Some(key) => {
+                self.key = key;
+                seed.deserialize(StrValue(key)).map(Some)
+            }
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, OverrideError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.parent.child(self.key))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.keys.len())
+    }
+}
+
+struct FillEnum<'a> {
+    variant: &'static str,
+    payload: Filler<'a>,
+}
+
+impl<'de, 'a> EnumAccess<'de> for FillEnum<'a> {
+    type Error = OverrideError;
+    type Variant = Filler<'a>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Filler<'a>), OverrideError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant: StrDeserializer<OverrideError> = self.variant.into_deserializer();
+        let value = seed.deserialize(variant)?;
+        Ok((value, self.payload))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Filler<'_> {
+    type Error = OverrideError;
+
+    fn unit_variant(self) -> Result<(), OverrideError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, OverrideError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_struct("", fields, visitor)
+    }
+}
+
+/// Deserializer that parses the string into requested kind of value.
+#[derive(Clone, Copy)]
+struct StrValue<'a>(&'a str);
+
+impl<'de> IntoDeserializer<'de, OverrideError> for StrValue<'_> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! parse_str {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+            where
+                V: Visitor<'de>,
+            {
+                match self.0.trim().parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(OverrideError::invalid_value(Unexpected::Str(self.0), &visitor)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for StrValue<'_> {
+    type Error = OverrideError;
+
+    parse_str! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_bytes(self.0.as_bytes())
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_bytes(self.0.as_bytes())
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        if self.0.trim() == "null" {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0.trim() {
+            "" | "null" => visitor.visit_unit(),
+            _ => Err(OverrideError::invalid_value(
+                Unexpected::Str(self.0),
+                &visitor,
+            )),
+        }
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        let elements: Vec<StrValue> = if self.0.trim().is_empty() {
+            Vec::new()
+        } else {
+            self.0.split(',').map(StrValue).collect()
+        };
+
+        let mut seq = SeqDeserializer::new(elements.into_iter());
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V>(self, _: usize, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        Err(OverrideError::invalid_type(
+            Unexpected::Str(self.0),
+            &visitor,
+        ))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        Err(OverrideError::invalid_type(
+            Unexpected::Str(self.0),
+            &visitor,
+        ))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        let variant: StrDeserializer<OverrideError> = self.0.trim().into_deserializer();
+        visitor.visit_enum(variant)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, OverrideError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+#[test]
+fn test_overrides() {
+    use std::{collections::BTreeMap, num::NonZeroU32};
+
+    #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+    enum Mode {
+        Off,
+        Fixed(u32),
+        Range { min: u32, max: NonZeroU32 },
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+    struct Limits {
+        max: NonZeroU32,
+        soft: u32,
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+    struct Config {
+        name: String,
+        limits: Limits,
+        mode: Mode,
+        ports: Vec<u16>,
+        env: BTreeMap<String, String>,
+        pair: (bool, Option<char>),
+    }
+
+    let err = from_nothing_with::<Config>(&Overrides::new()).unwrap_err();
+    assert_eq!(
+        err,
+        OverrideError::Message("`limits.max` cannot be nothing and must be overridden".into())
+    );
+
+    let mut overrides = Overrides::new();
+    overrides
+        .parse("limits.max=10")
+        .unwrap()
+        .parse("mode.Range.max=3")
+        .unwrap()
+        .parse("ports.2=80")
+        .unwrap()
+        .parse("env.HOME=/root")
+        .unwrap()
+        .parse("pair.1=x")
+        .unwrap()
+        .set("name", serde_json::Value::String("test".into()))
+        .unwrap();
+
+    let config: Config = from_nothing_with(&overrides).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            name: "test".into(),
+            limits: Limits {
+                max: NonZeroU32::new(10).unwrap(),
+                soft: 0,
+            },
+            mode: Mode::Range {
+                min: 0,
+                max: NonZeroU32::new(3).unwrap(),
+            },
+            ports: vec![0, 0, 80],
+            env: vec![("HOME".into(), "/root".into())].into_iter().collect(),
+            pair: (false, Some('x')),
+        }
+    );
+
+    let mut overrides = Overrides::new();
+    overrides
+        .parse("limits.max=1")
+        .unwrap()
+        .parse("mode=Fixed")
+        .unwrap();
+    let err = from_nothing_with::<Config>(&overrides).unwrap_err();
+    assert!(matches!(err, OverrideError::Message(message) if message.starts_with("`mode`: ")));
+
+    let mut overrides = Overrides::new();
+    overrides
+        .parse("limits.max=1")
+        .unwrap()
+        .set(
+            "mode",
+            &mut serde_json::Deserializer::from_str(r#"{"Fixed": 5}"#),
+        )
+        .unwrap();
+    let config: Config = from_nothing_with(&overrides).unwrap();
+    assert_eq!(config.mode, Mode::Fixed(5));
+
+    let mut overrides = Overrides::new();
+    overrides
+        .parse("limits.max=1")
+        .unwrap()
+        .parse("limits.hard=2")
+        .unwrap();
+    let err = from_nothing_with::<Config>(&overrides).unwrap_err();
+    assert_eq!(err, OverrideError::UnknownPath("limits.hard".into()));
+
+    assert_eq!(
+        Overrides::new().parse("no assignment").unwrap_err(),
+        OverrideError::Syntax("no assignment".into())
+    );
+
+    // Without overrides self-describing types get the same value as from `Nothing`.
+    #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+    #[serde(untagged)]
+    enum Untagged {
+        A,
+        B(u32),
+    }
+
+    let value = from_nothing_with::<serde_json::Value>(&Overrides::new()).unwrap();
+    assert_eq!(Some(value), crate::from_nothing::<serde_json::Value>());
+    let value = from_nothing_with::<Untagged>(&Overrides::new()).unwrap();
+    assert_eq!(Some(value), crate::from_nothing::<Untagged>());
+    assert_eq!(crate::from_nothing::<Untagged>(), Some(Untagged::A));
+
+    let mut overrides = Overrides::new();
+    overrides.parse("a.b=1").unwrap();
+    let value = from_nothing_with::<serde_json::Value>(&overrides).unwrap();
+    assert_eq!(value, serde_json::json!({ "a": { "b": "1" } }));
+}
@@ -0,0 +1,111 @@
+//!
+//! Protocol for types to declare their own nothingness.
+//!
+//! `Serialize` implementation may wrap its content into a newtype struct
+//! with one of the reserved names defined in this module.
+//! `Nothing` serializer recognizes those names in `serialize_newtype_struct`
+//! and answers without serializing the content:
+//!
+//! * newtype struct named [`NOTHING`] is nothing,
+//! * newtype struct named [`SOMETHING`] is never nothing.
+//!
+//! Any other serializer sees a plain newtype struct and,
+//! as most formats do, serializes the content transparently.
+//!
+//! This is useful for types with expensive `Serialize` implementation
+//! that can tell whether they are nothing cheaply.
+//!
+//! ```
+//! # use serde::{Serialize, Serializer};
+//! struct Blob {
+//!     data: Vec<u8>,
+//! }
+//!
+//! impl Serialize for Blob {
+//!     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+//!         serde_nothing::protocol::serialize_declared(serializer, self.data.is_empty(), &self.data)
+//!     }
+//! }
+//!
+//! assert!(serde_nothing::is_nothing(&Blob { data: vec![] }));
+//! assert!(!serde_nothing::is_nothing(&Blob { data: vec![1, 2, 3] }));
+//! ```
+//!
+//! # Stability
+//!
+//! Values of [`NOTHING`] and [`SOMETHING`] are part of the public API
+//! and will not change without a breaking release.
+//! Implementors may use the string values directly
+//! without depending on this crate.
+//!
+//! Declaration is trusted: content of declared nothing value
+//! must be nothing itself, otherwise `Nothing` serializer and other serializers
+//! would disagree on the value.
+//!
+
+use serde::{Serialize, Serializer};
+
+/// Newtype struct name that declares the value to be nothing.
+pub const NOTHING: &str = "$serde_nothing::Nothing";
+
+/// Newtype struct name that declares the value to be never nothing.
+pub const SOMETHING: &str = "$serde_nothing::Something";
+
+/// Serializes `value` wrapped into newtype struct that declares nothingness.
+///
+/// `Nothing` serializer does not serialize `value`,
+/// other serializers serialize it as newtype struct content.
+#[inline]
+pub fn serialize_declared<S, T>(serializer: S, nothing: bool, value: &T) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ?Sized + Serialize,
+{
+    let name = if nothing { NOTHING } else { SOMETHING };
+    serializer.serialize_newtype_struct(name, value)
+}
+
+#[test]
+fn test_declared() {
+    use core::cell::Cell;
+
+    struct Counted<'a> {
+        nothing: bool,
+        count: &'a Cell<usize>,
+    }
+
+    impl Serialize for Counted<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            struct Content<'a>(&'a Cell<usize>);
+
+            impl Serialize for Content<'_> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.0.set(self.0.get() + 1);
+                    serializer.serialize_u32(42)
+                }
+            }
+
+            serialize_declared(serializer, self.nothing, &Content(self.count))
+        }
+    }
+
+    let count = Cell::new(0);
+
+    assert!(crate::is_nothing(&Counted {
+        nothing: true,
+        count: &count,
+    }));
+    assert!(!crate::is_nothing(&Counted {
+        nothing: false,
+        count: &count,
+    }));
+    assert_eq!(count.get(), 0);
+
+    let json = serde_json::to_string(&Counted {
+        nothing: false,
+        count: &count,
+    })
+    .unwrap();
+    assert_eq!(json, "42");
+    assert_eq!(count.get(), 1);
+}
@@ -0,0 +1,664 @@
+//!
+//! Registry of custom nothing rules keyed by serde type names.
+//!
+//! Serde data model passes type names to `serialize_newtype_struct`, `deserialize_struct`
+//! and similar methods. `Nothing` looks those names up in the registry,
+//! which allows changing definition of "nothing" for types that can't be modified,
+//! like types from third-party crates.
+//!
+//! Registry is global and requires `std` feature.
+//! Without it the registry is always empty.
+//!
+//! Rules are applied by `Nothing` serializer and deserializer.
+//! Once any rule is registered, derived `IsNothing` implementations
+//! check values with `is_nothing` function, so rules apply to them as well.
+//!
+
+use core::{
+    convert::TryFrom,
+    fmt::{self, Write},
+};
+
+use serde::{
+    de::{DeserializeSeed, Deserializer, SeqAccess, Visitor},
+    forward_to_deserialize_any,
+    ser::{Impossible, Serialize, Serializer},
+};
+
+use crate::{Nothing, NothingDeserializeError, NothingSerializeError};
+
+/// Primitive value of serde data model.
+///
+/// Integers of all sizes are compared by value,
+/// so `Primitive::U64(0)` matches `0u16` as well.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Primitive {
+    /// `bool` value.
+    Bool(bool),
+    /// Signed integer value.
+    I64(i64),
+    /// Unsigned integer value.
+    U64(u64),
+    /// Floating point value.
+    F64(f64),
+    /// `char` value.
+    Char(char),
+    /// String value.
+    Str(&'static str),
+    /// Byte string value.
+    Bytes(&'static [u8]),
+    /// Unit value.
+    Unit,
+}
+
+/// Custom nothing rule for a named type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rule {
+    /// Values of the type are never nothing.
+    /// Serializing with `Nothing` fails and deserializing from `Nothing` fails.
+    Never,
+
+    /// Value of the type is nothing when its content equals the primitive.
+    /// Deserializing from `Nothing` produces the type from the primitive.
+    ///
+    /// Content of a newtype struct is its inner value.
+    /// Content of other types is never equal to a primitive,
+    /// except unit struct which matches `Primitive::Unit`.
+    /// Structs with named fields are described with `Rule::Fields`.
+    Equals(Primitive),
+
+    /// Value of the struct is nothing when listed fields equal their primitives
+    /// and other fields are nothing.
+    /// Deserializing from `Nothing` produces listed fields from the primitives
+    /// and other fields from `Nothing`.
+    ///
+    /// Applies to structs with named fields only,
+    /// values of other types are never nothing.
+    Fields(&'static [(&'static str, Primitive)]),
+}
+
+#[cfg(feature = "std")]
+mod global {
+    use std::{
+        collections::BTreeMap,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            PoisonError, RwLock,
+        },
+    };
+
+    use super::Rule;
+
+    static RULES: RwLock<BTreeMap<&'static str, Rule>> = RwLock::new(BTreeMap::new());
+
+    // Allows skipping the lock when no rules are registered.
+    // Updated under the write lock, so it never lags behind the map.
+    static USED: AtomicBool = AtomicBool::new(false);
+
+    /// Registers nothing rule for type with specified serde name.
+    /// Returns previously registered rule, if any.
+    ///
+    /// The name is one passed by `Serialize` and `Deserialize` implementations
+    /// to serde methods, for derived implementations it is name of the type
+    /// or value of `#[serde(rename = "...")]` attribute.
+    pub fn register(name: &'static str, rule: Rule) -> Option<Rule> {
+        let mut rules = RULES.write().unwrap_or_else(PoisonError::into_inner);
+        USED.store(true, Ordering::Release);
+        rules.insert(name, rule)
+    }
+
+    /// Removes nothing rule for type with specified serde name.
+    /// Returns removed rule, if any.
+    pub fn unregister(name: &str) -> Option<Rule> {
+        let mut rules = RULES.write().unwrap_or_else(PoisonError::into_inner);
+        let rule = rules.remove(name);
+        USED.store(!rules.is_empty(), Ordering::Release);
+        rule
+    }
+
+    /// Returns true if any rule is registered.
+    #[inline]
+    pub(crate) fn used() -> bool {
+        USED.load(Ordering::Acquire)
+    }
+
+    #[inline]
+    pub(crate) fn lookup(name: &str) -> Option<Rule> {
+        if !USED.load(Ordering::Acquire) {
+            return None;
+        }
+        RULES
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+            .copied()
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::global::{register, unregister};
+
+#[cfg(feature = "std")]
+pub(crate) use self::global::{lookup, used};
+
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn lookup(_: &str) -> Option<Rule> {
+    None
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn used() -> bool {
+    false
+}
+
+/// Checks that value serialized as newtype struct content matches the rule.
+pub(crate) fn check_newtype<T>(rule: Rule, value: &T) -> Result<(), NothingSerializeError>
+where
+    T: ?Sized + Serialize,
+{
+    match rule {
+        Rule::Never | Rule::Fields(_) => Err(NothingSerializeError),
+        Rule::Equals(primitive) => value.serialize(MatchPrimitive(primitive)),
+    }
+}
+
+/// Returns field rules for the struct with the rule.
+pub(crate) fn struct_fields(
+    rule: Rule,
+) -> Result<&'static [(&'static str, Primitive)], NothingSerializeError> {
+    match rule {
+        Rule::Fields(fields) => Ok(fields),
+        _ => Err(NothingSerializeError),
+    }
+}
+
+/// Checks that struct field matches the primitive listed for it, if any,
+/// or is nothing otherwise.
+pub(crate) fn check_field<T>(
+    fields: &[(&'static str, Primitive)],
+    key: &str,
+    value: &T,
+) -> Result<(), NothingSerializeError>
+where
+    T: ?Sized + Serialize,
+{
+    match fields.iter().find(|(name, _)| *name == key) {
+        Some(&(_, primitive)) => value.serialize(MatchPrimitive(primitive)),
+        None => value.serialize(Nothing),
+    }
+}
+
+/// Checks that skipped struct field has no primitive listed for it.
+pub(crate) fn check_skipped(
+    fields: &[(&'static str, Primitive)],
+    key: &str,
+) -> Result<(), NothingSerializeError> {
+    if fields.iter().any(|(name, _)| *name == key) {
+        Err(NothingSerializeError)
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks that unit struct matches the rule.
+pub(crate) fn check_unit(rule: Rule) -> Result<(), NothingSerializeError> {
+    match rule {
+        Rule::Equals(Primitive::Unit) => Ok(()),
+        _ => Err(NothingSerializeError),
+    }
+}
+
+/// Deserializer that produces value according to the rule.
+pub(crate) fn deserializer(rule: Rule) -> Result<PrimitiveDeserializer, NothingDeserializeError> {
+    match rule {
+        Rule::Never | Rule::Fields(_) => Err(NothingDeserializeError),
+        Rule::Equals(primitive) => Ok(PrimitiveDeserializer(primitive)),
+    }
+}
+
+/// Sequence of struct fields produced according to the rule.
+pub(crate) fn struct_access(
+    rule: Rule,
+    names: &'static [&'static str],
+) -> Result<FieldsAccess, NothingDeserializeError> {
+    match rule {
+        Rule::Fields(fields) => Ok(FieldsAccess { fields, names }),
+        _ => Err(NothingDeserializeError),
+    }
+}
+
+/// Serializer that succeeds only if serialized value equals the primitive.
+struct MatchPrimitive(Primitive);
+
+impl MatchPrimitive {
+    fn check(self, matches: bool) -> Result<(), NothingSerializeError> {
+        if matches {
+            Ok(())
+        } else {
+            Err(NothingSerializeError)
+        }
+    }
+
+    fn check_i128(self, v: i128) -> Result<(), NothingSerializeError> {
+        let matches = match self.0 {
+            Primitive::I64(p) => i128::from(p) == v,
+            Primitive::U64(p) => i128::from(p) == v,
+            _ => false,
+        };
+        self.check(matches)
+    }
+
+    fn check_u128(self, v: u128) -> Result<(), NothingSerializeError> {
+        let matches = match self.0 {
+            Primitive::I64(p) => u128::try_from(p) == Ok(v),
+            Primitive::U64(p) => u128::from(p) == v,
+            _ => false,
+        };
+        self.check(matches)
+    }
+}
+
+macro_rules! match_int {
+    ($($method:ident: $ty:ty => $check:ident,)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<(), NothingSerializeError> {
+                self.$check(v.into())
+            }
+        )*
+    };
+}
+
+impl Serializer for MatchPrimitive {
+    type Ok = ();
+    type Error = NothingSerializeError;
+    type SerializeSeq = Impossible<(), NothingSerializeError>;
+    type SerializeTuple = Impossible<(), NothingSerializeError>;
+    type SerializeTupleStruct = Impossible<(), NothingSerializeError>;
+    type SerializeTupleVariant = Impossible<(), NothingSerializeError>;
+    type SerializeMap = Impossible<(), NothingSerializeError>;
+    type SerializeStruct = Impossible<(), NothingSerializeError>;
+    type SerializeStructVariant = Impossible<(), NothingSerializeError>;
+
+    match_int! {
+        serialize_i8: i8 => check_i128,
+        serialize_i16: i16 => check_i128,
+        serialize_i32: i32 => check_i128,
+        serialize_i64: i64 => check_i128,
+        serialize_i128: i128 => check_i128,
+        serialize_u8: u8 => check_u128,
+        serialize_u16: u16 => check_u128,
+        serialize_u32: u32 => check_u128,
+        serialize_u64: u64 => check_u128,
+        serialize_u128: u128 => check_u128,
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<(), NothingSerializeError> {
+        let matches = self.0 == Primitive::Bool(v);
+        self.check(matches)
+    }
+    fn serialize_f32(self, v: f32) -> Result<(), NothingSerializeError> {
+        self.serialize_f64(v.into())
+    }
+    fn serialize_f64(self, v: f64) -> Result<(), NothingSerializeError> {
+        let matches = self.0 == Primitive::F64(v);
+        self.check(matches)
+    }
+    fn serialize_char(self, v: char) -> Result<(), NothingSerializeError> {
+        let matches = self.0 == Primitive::Char(v);
+        self.check(matches)
+    }
+    fn serialize_str(self, v: &str) -> Result<(), NothingSerializeError> {
+        let matches = matches!(self.0, Primitive::Str(p) if p == v);
+        self.check(matches)
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), NothingSerializeError> {
+        let matches = matches!(self.0, Primitive::Bytes(p) if p == v);
+        self.check(matches)
+    }
+    fn serialize_none(self) -> Result<(), NothingSerializeError> {
+        Err(NothingSerializeError)
+    }
+    fn serialize_some<T>(self, _: &T) -> Result<(), NothingSerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(NothingSerializeError)
+    }
+    fn serialize_unit(self) -> Result<(), NothingSerializeError> {
+        let matches = self.0 == Primitive::Unit;
+        self.check(matches)
+    }
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), NothingSerializeError> {
+        self.serialize_unit()
+    }
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<(), NothingSerializeError> {
+        Err(NothingSerializeError)
+    }
+    fn serialize_newtype_struct<T>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), NothingSerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), NothingSerializeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(NothingSerializeError)
+    }
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, NothingSerializeError> {
+        Err(NothingSerializeError)
+    }
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, NothingSerializeError> {
+        Err(NothingSerializeError)
+    }
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, NothingSerializeError> {
+        Err(NothingSerializeError)
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, NothingSerializeError> {
+        Err(NothingSerializeError)
+    }
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, NothingSerializeError> {
+        Err(NothingSerializeError)
+    }
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, NothingSerializeError> {
+        Err(NothingSerializeError)
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, NothingSerializeError> {
+        Err(NothingSerializeError)
+    }
+
+    fn collect_str<T>(self, value: &T) -> Result<(), NothingSerializeError>
+    where
+        T: ?Sized + fmt::Display,
+    {
+        struct WriteExpected<'a>(&'a str);
+
+        impl Write for WriteExpected<'_> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                match self.0.strip_prefix(s) {
+                    Some(rest) => {
+                        self.0 = rest;
+                        Ok(())
+                    }
+                    None => Err(fmt::Error),
+                }
+            }
+        }
+
+        match self.0 {
+            Primitive::Str(expected) => {
+                let mut writer = WriteExpected(expected);
+                let matches = write!(writer, "{}", value).is_ok() && writer.0.is_empty();
+                self.check(matches)
+            }
+            _ => Err(NothingSerializeError),
+        }
+    }
+}
+
+/// Deserializer that visits the primitive.
+pub(crate) struct PrimitiveDeserializer(Primitive);
+
+impl<'de> Deserializer<'de> for PrimitiveDeserializer {
+    type Error = NothingDeserializeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Primitive::Bool(v) => visitor.visit_bool(v),
+            Primitive::I64(v) => visitor.visit_i64(v),
+            Primitive::U64(v) => visitor.visit_u64(v),
+            Primitive::F64(v) => visitor.visit_f64(v),
+            Primitive::Char(v) => visitor.visit_char(v),
+            Primitive::Str(v) => visitor.visit_borrowed_str(v),
+            Primitive::Bytes(v) => visitor.visit_borrowed_bytes(v),
+            Primitive::Unit => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Visits struct fields in order,
+/// listed fields from their primitives and others from `Nothing`.
+pub(crate) struct FieldsAccess {
+    fields: &'static [(&'static str, Primitive)],
+    names: &'static [&'static str],
+}
+
+impl<'de> SeqAccess<'de> for FieldsAccess {
+    type Error = NothingDeserializeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, NothingDeserializeError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let (name, rest) = match self.names.split_first() {
+            Some(split) => split,
+            None => return Ok(None),
+        };
+        self.names = rest;
+
+        match self.fields.iter().find(|(field, _)| field == name) {
+            Some(&(_, primitive)) => seed.deserialize(PrimitiveDeserializer(primitive)).map(Some),
+            None => seed.deserialize(Nothing).map(Some),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.names.len())
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_registry() {
+    use serde::Deserialize;
+
+    use crate::{from_nothing, is_nothing, IsNothing};
+
+    /// Unregisters the rule when test ends, even if it fails,
+    /// so the rule does not leak into other tests.
+    struct Registered(&'static str);
+
+    impl Registered {
+        fn new(name: &'static str, rule: Rule) -> Self {
+            register(name, rule);
+            Registered(name)
+        }
+    }
+
+    impl Drop for Registered {
+        fn drop(&mut self) {
+            unregister(self.0);
+        }
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    #[serde(rename = "test_registry::Timestamp")]
+    struct Timestamp(String);
+
+    #[derive(
+        Debug,
+        PartialEq,
+        serde_derive::Serialize,
+        serde_derive::Deserialize,
+        serde_nothing_derive::IsNothing,
+    )]
+    #[serde(rename = "test_registry::Port")]
+    struct Port(u16);
+
+    #[derive(serde_derive::Serialize, serde_nothing_derive::IsNothing)]
+    struct Cfg {
+        port: Port,
+    }
+
+    // `Serialize` depends on `T`, so fields are checked one by one.
+    #[derive(serde_derive::Serialize, serde_nothing_derive::IsNothing)]
+    struct Holder<T> {
+        port: Port,
+        value: T,
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    #[serde(rename = "test_registry::Endpoint")]
+    struct Endpoint {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    #[serde(rename = "test_registry::Point")]
+    struct Point {
+        x: u32,
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    #[serde(rename = "test_registry::Secret")]
+    struct Secret {
+        key: String,
+    }
+
+    struct Display(u32);
+
+    impl fmt::Display for Display {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "#{}", self.0)
+        }
+    }
+
+    impl Serialize for Display {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_newtype_struct("test_registry::Display", &format_args!("{}", self))
+        }
+    }
+
+    const EPOCH: &str = "1970-01-01T00:00:00Z";
+
+    let _timestamp = Registered::new(
+        "test_registry::Timestamp",
+        Rule::Equals(Primitive::Str(EPOCH)),
+    );
+    let port = Registered::new("test_registry::Port", Rule::Equals(Primitive::U64(80)));
+    let _endpoint = Registered::new(
+        "test_registry::Endpoint",
+        Rule::Fields(&[("port", Primitive::U64(80))]),
+    );
+    let _point = Registered::new("test_registry::Point", Rule::Equals(Primitive::U64(0)));
+    let _secret = Registered::new("test_registry::Secret", Rule::Never);
+    let _display = Registered::new("test_registry::Display", Rule::Equals(Primitive::Str("#7")));
+
+    assert!(is_nothing(&Timestamp(EPOCH.into())));
+    assert!(!is_nothing(&Timestamp(String::new())));
+    assert_eq!(from_nothing(), Some(Timestamp(EPOCH.into())));
+
+    assert!(is_nothing(&Port(80)));
+    assert!(!is_nothing(&Port(0)));
+    assert_eq!(from_nothing(), Some(Port(80)));
+
+    let endpoint = Endpoint {
+        host: String::new(),
+        port: 80,
+    };
+    assert!(is_nothing(&endpoint));
+    assert!(!is_nothing(&Endpoint {
+        port: 0,
+        ..endpoint
+    }));
+    assert!(!is_nothing(&Endpoint {
+        host: "localhost".into(),
+        port: 80,
+    }));
+    assert_eq!(
+        from_nothing(),
+        Some(Endpoint {
+            host: String::new(),
+            port: 80,
+        })
+    );
+
+    // `Equals` does not describe structs with named fields,
+    // both directions fail instead of disagreeing.
+    assert!(!is_nothing(&Point { x: 0 }));
+    assert_eq!(from_nothing::<Point>(), None);
+
+    assert!(!is_nothing(&Secret { key: String::new() }));
+    assert_eq!(from_nothing::<Secret>(), None);
+
+    assert!(is_nothing(&Display(7)));
+    assert!(!is_nothing(&Display(70)));
+
+    // Derived `IsNothing` agrees with `is_nothing` under rules.
+    for port in [0, 80] {
+        let cfg = Cfg { port: Port(port) };
+        assert_eq!(IsNothing::is_nothing(&cfg), port == 80);
+        assert_eq!(is_nothing(&cfg), port == 80);
+        let holder = Holder {
+            port: Port(port),
+            value: 0u32,
+        };
+        assert_eq!(IsNothing::is_nothing(&holder), port == 80);
+        assert_eq!(is_nothing(&holder), port == 80);
+    }
+
+    drop(port);
+    assert_eq!(lookup("test_registry::Port"), None);
+    assert!(is_nothing(&Port(0)));
+    assert!(IsNothing::is_nothing(&Cfg { port: Port(0) }));
+    assert_eq!(Port::deserialize(crate::Nothing), Ok(Port(0)));
+}
@@ -8,7 +8,16 @@ use serde::{
     Serialize, Serializer,
 };
 
-use crate::Nothing;
+use crate::{protocol, registry, registry::Primitive, Nothing};
+
+/// Fails if there is a custom rule for the named type that is not a primitive.
+#[inline]
+fn check_compound(name: &'static str) -> Result<(), NothingSerializeError> {
+    match registry::lookup(name) {
+        None => Ok(()),
+        Some(_) => Err(NothingSerializeError),
+    }
+}
 
 /// Error type for `Nothing` serializer.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -41,7 +50,7 @@ impl Serializer for Nothing {
     type SerializeTupleStruct = Self;
     type SerializeTupleVariant = Self;
     type SerializeMap = Self;
-    type SerializeStruct = Self;
+    type SerializeStruct = NothingStruct;
     type SerializeStructVariant = Self;
 
     fn serialize_bool(self, v: bool) -> Result<(), NothingSerializeError> {
@@ -165,30 +174,43 @@ impl Serializer for Nothing {
     fn serialize_unit(self) -> Result<(), NothingSerializeError> {
         Ok(())
     }
-    fn serialize_unit_struct(self, _: &'static str) -> Result<(), NothingSerializeError> {
-        Ok(())
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), NothingSerializeError> {
+        match registry::lookup(name) {
+            None => Ok(()),
+            Some(rule) => registry::check_unit(rule),
+        }
     }
     fn serialize_unit_variant(
         self,
-        _: &'static str,
+        name: &'static str,
         _: u32,
         _: &'static str,
     ) -> Result<(), NothingSerializeError> {
-        Ok(())
+        check_compound(name)
     }
     fn serialize_newtype_struct<T: ?Sized>(
         self,
-        _: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<(), NothingSerializeError>
     where
         T: Serialize,
     {
-        value.serialize(self)
+        if name == protocol::NOTHING {
+            return Ok(());
+        }
+        if name == protocol::SOMETHING {
+            return Err(NothingSerializeError);
+        }
+
+        match registry::lookup(name) {
+            None => value.serialize(self),
+            Some(rule) => registry::check_newtype(rule, value),
+        }
     }
     fn serialize_newtype_variant<T: ?Sized>(
         self,
-        _: &'static str,
+        name: &'static str,
         _: u32,
         _: &'static str,
         value: &T,
@@ -196,6 +218,7 @@ impl Serializer for Nothing {
     where
         T: Serialize,
     {
+        check_compound(name)?;
         value.serialize(self)
     }
     fn serialize_seq(self, _: Option<usize>) -> Result<Self, NothingSerializeError> {
@@ -206,33 +229,44 @@ impl Serializer for Nothing {
     }
     fn serialize_tuple_struct(
         self,
-        _: &'static str,
+        name: &'static str,
         _: usize,
     ) -> Result<Self, NothingSerializeError> {
+        check_compound(name)?;
         Ok(self)
     }
     fn serialize_tuple_variant(
         self,
-        _: &'static str,
+        name: &'static str,
         _: u32,
         _: &'static str,
         _: usize,
     ) -> Result<Self, NothingSerializeError> {
+        check_compound(name)?;
         Ok(self)
     }
     fn serialize_map(self, _: Option<usize>) -> Result<Self, NothingSerializeError> {
         Ok(self)
     }
-    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, NothingSerializeError> {
-        Ok(self)
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _: usize,
+    ) -> Result<NothingStruct, NothingSerializeError> {
+        let fields = match registry::lookup(name) {
+            None => &[][..],
+            Some(rule) => registry::struct_fields(rule)?,
+        };
+        Ok(NothingStruct { fields })
     }
     fn serialize_struct_variant(
         self,
-        _: &'static str,
+        name: &'static str,
         _: u32,
         _: &'static str,
         _: usize,
     ) -> Result<Self, NothingSerializeError> {
+        check_compound(name)?;
         Ok(self)
     }
 
@@ -365,23 +399,29 @@ impl SerializeMap for Nothing {
     }
 }
 
-impl SerializeStruct for Nothing {
+/// Struct serializer of `Nothing`.
+/// Checks fields against registered rule of the struct, if any.
+pub struct NothingStruct {
+    fields: &'static [(&'static str, Primitive)],
+}
+
+impl SerializeStruct for NothingStruct {
     type Ok = ();
     type Error = NothingSerializeError;
 
     fn serialize_field<T: ?Sized>(
         &mut self,
-        _: &'static str,
+        key: &'static str,
         value: &T,
     ) -> Result<(), NothingSerializeError>
     where
         T: Serialize,
     {
-        value.serialize(*self)
+        registry::check_field(self.fields, key, value)
     }
 
-    fn skip_field(&mut self, _: &'static str) -> Result<(), NothingSerializeError> {
-        Ok(())
+    fn skip_field(&mut self, key: &'static str) -> Result<(), NothingSerializeError> {
+        registry::check_skipped(self.fields, key)
     }
 
     fn end(self) -> Result<(), NothingSerializeError> {
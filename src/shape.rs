@@ -0,0 +1,1251 @@
+//!
+//! Shape of a type discovered from its `Deserialize` implementation.
+//!
+//! [`describe`] deserializes the type many times with a tracing deserializer
+//! that records every request the `Deserialize` implementation makes:
+//! struct names and fields, enum variants, tuple lengths and primitive kinds.
+//! Each pass explores one more part of the type: `Some` for options,
+//! single element for sequences and maps, every variant of enums.
+//! Every part is also deserialized from `Nothing` to find out whether it can be nothing.
+//! Structs are also deserialized with each field left out to find out which fields can be omitted.
+//!
+//! Shape is only as precise as the `Deserialize` implementation is.
+//! Self-describing implementations that call `deserialize_any` are opaque,
+//! and parts that follow a part that cannot be constructed in any way may stay unknown.
+//!
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    vec::Vec,
+};
+
+use serde::{
+    de::{
+        value::{StrDeserializer, U32Deserializer},
+        DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+        Visitor,
+    },
+    Deserialize, Deserializer,
+};
+
+use crate::{Nothing, NothingDeserializeError, Something};
+
+/// Limit on number of deserialization passes [`describe`] makes.
+const MAX_PASSES: usize = 16384;
+
+/// Shape of a type or a part of it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shape {
+    /// Kind of the value.
+    pub kind: Kind,
+
+    /// Whether this part can be deserialized from `Nothing`.
+    pub nothing: bool,
+}
+
+/// Kind of a value in terms of `serde` data model.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Kind {
+    /// `bool` value.
+    Bool,
+    /// `i8` value.
+    I8,
+    /// `i16` value.
+    I16,
+    /// `i32` value.
+    I32,
+    /// `i64` value.
+    I64,
+    /// `i128` value.
+    I128,
+    /// `u8` value.
+    U8,
+    /// `u16` value.
+    U16,
+    /// `u32` value.
+    U32,
+    /// `u64` value.
+    U64,
+    /// `u128` value.
+    U128,
+    /// `f32` value.
+    F32,
+    /// `f64` value.
+    F64,
+    /// `char` value.
+    Char,
+    /// String value.
+    Str,
+    /// Byte string value.
+    Bytes,
+    /// Unit value.
+    Unit,
+
+    /// Optional value.
+    Option(Box<Shape>),
+
+    /// Sequence of elements.
+    Seq(Box<Shape>),
+
+    /// Tuple or fixed size array.
+    Tuple(Vec<Shape>),
+
+    /// Map of keys to values.
+    /// Structs with `#[serde(flatten)]` fields are maps with `Kind::Any` values.
+    Map {
+        /// Shape of keys.
+        key: Box<Shape>,
+        /// Shape of values.
+        value: Box<Shape>,
+    },
+
+    /// Unit struct.
+    UnitStruct {
+        /// Serde name of the struct.
+        name: &'static str,
+    },
+
+    /// Newtype struct.
+    NewtypeStruct {
+        /// Serde name of the struct.
+        name: &'static str,
+        /// Shape of the wrapped value.
+        inner: Box<Shape>,
+    },
+
+    /// Tuple struct.
+    TupleStruct {
+        /// Serde name of the struct.
+        name: &'static str,
+        /// Shapes of the fields in order.
+        fields: Vec<Shape>,
+    },
+
+    /// Struct with named fields.
+    Struct {
+        /// Serde name of the struct.
+        name: &'static str,
+        /// Fields in order of declaration.
+        fields: Vec<Field>,
+    },
+
+    /// Externally tagged enum.
+    Enum {
+        /// Serde name of the enum.
+        name: &'static str,
+        /// Variants in order of declaration.
+        variants: Vec<Variant>,
+    },
+
+    /// Field or variant identifier.
+    Identifier,
+
+    /// Self-describing value, its content is decided by the input.
+    /// Types that call `deserialize_any`, like untagged and internally tagged enums,
+    /// are opaque and described with this kind as a whole.
+    Any,
+
+    /// Struct or enum that contains itself.
+    /// Its shape is described by the enclosing part with the same name.
+    Recursive {
+        /// Serde name of the type being revisited.
+        name: &'static str,
+    },
+
+    /// Part that was never reached.
+    Unknown,
+}
+
+/// Named field of a struct or struct variant.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    /// Serde name of the field.
+    pub name: &'static str,
+
+    /// Shape of the field value.
+    pub shape: Shape,
+
+    /// Whether the struct can be deserialized with this field missing,
+    /// like fields with `#[serde(default)]` and optional fields.
+    pub omittable: bool,
+}
+
+/// Enum variant.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variant {
+    /// Serde name of the variant.
+    pub name: &'static str,
+
+    /// Whether this variant can be deserialized with `Nothing` payload.
+    pub nothing: bool,
+
+    /// Payload of the variant.
+    pub payload: Payload,
+}
+
+/// Payload of an enum variant.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Payload {
+    /// Variant without payload.
+    Unit,
+    /// Variant with single unnamed field.
+    Newtype(Box<Shape>),
+    /// Variant with unnamed fields.
+    Tuple(Vec<Shape>),
+    /// Variant with named fields.
+    Struct(Vec<Field>),
+
+    /// Variant that was never reached.
+    Unknown,
+}
+
+/// Describes shape of the type.
+///
+/// Parts that call `deserialize_any` are opaque and come out as `Kind::Any`,
+/// this includes untagged and internally tagged enums
+/// and contents of structs with `#[serde(flatten)]` fields.
+///
+/// ```
+/// # use serde_nothing::shape::{describe, Kind};
+/// #[derive(serde_derive::Deserialize)]
+/// struct Config {
+///     name: String,
+///     port: std::num::NonZeroU16,
+/// }
+///
+/// let shape = describe::<Config>();
+/// match shape.kind {
+///     Kind::Struct { name, fields } => {
+///         assert_eq!(name, "Config");
+///         assert_eq!(fields[0].name, "name");
+///         assert!(fields[0].shape.nothing);
+///         assert!(!fields[0].omittable);
+///         assert_eq!(fields[1].shape.kind, Kind::U16);
+///         assert!(!fields[1].shape.nothing);
+///     }
+///     _ => unreachable!(),
+/// }
+/// assert!(!shape.nothing);
+/// ```
+pub fn describe<'de, T>() -> Shape
+where
+    T: Deserialize<'de>,
+{
+    let state = RefCell::new(State::default());
+
+    let mut done = BTreeSet::new();
+    let mut queue = VecDeque::new();
+    queue.push_back((Vec::new(), Choice::Explore(0)));
+
+    let mut passes = 0;
+    while let Some((path, choice)) = queue.pop_front() {
+        if passes == MAX_PASSES {
+            break;
+        }
+        passes += 1;
+
+        state.borrow_mut().prepare(&path, choice);
+        let _ = T::deserialize(Tracer {
+            state: &state,
+            path: Vec::new(),
+            names: Vec::new(),
+            omit: None,
+        });
+        done.insert((path, choice));
+
+        for task in state.borrow().tasks() {
+            if !done.contains(&task) && !queue.contains(&task) {
+                queue.push_back(task);
+            }
+        }
+    }
+
+    let state = state.into_inner();
+    state.build(&mut Vec::new())
+}
+
+/// How tracer deserializes a part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Choice {
+    /// Deserialize whole part from `Nothing`.
+    Nothing,
+
+    /// Trace the part.
+    /// Index selects enum variant or struct field to start with.
+    Explore(u32),
+
+    /// Trace the struct as a map without the field with the index.
+    Omit(u32),
+}
+
+/// Recorded request of the `Deserialize` implementation.
+#[derive(Clone, Debug)]
+enum Raw {
+    Leaf(Kind),
+    Option,
+    Seq,
+    Tuple(usize),
+    Map,
+    UnitStruct(&'static str),
+    NewtypeStruct(&'static str),
+    TupleStruct(&'static str, usize),
+    Struct(&'static str, &'static [&'static str]),
+    Enum(&'static str, &'static [&'static str]),
+    Recursive(&'static str),
+    UnitPayload,
+    NewtypePayload,
+    TuplePayload(usize),
+    StructPayload(&'static [&'static str]),
+}
+
+struct Record {
+    raw: Raw,
+    explored: BTreeMap<u32, bool>,
+    omitted: BTreeMap<u32, bool>,
+    nothing: Option<bool>,
+}
+
+#[derive(Default)]
+struct State {
+    records: BTreeMap<Vec<u32>, Record>,
+    choices: BTreeMap<Vec<u32>, Choice>,
+}
+
+impl State {
+    /// Sets choices to reach `path` and deserialize it with `choice`.
+    fn prepare(&mut self, path: &[u32], choice: Choice) {
+        self.choices.clear();
+        for len in 0..path.len() {
+            let prefix = &path[..len];
+            let explore = match self.records.get(prefix).map(|record| &record.raw) {
+                Some(Raw::Enum(..)) | Some(Raw::Struct(..)) | Some(Raw::StructPayload(..)) => {
+                    Choice::Explore(path[len])
+                }
+                _ => Choice::Explore(0),
+            };
+            self.choices.insert(prefix.to_vec(), explore);
+        }
+        self.choices.insert(path.to_vec(), choice);
+    }
+
+    fn choice(&self, path: &[u32]) -> Choice {
+        if let Some(choice) = self.choices.get(path) {
+            return *choice;
+        }
+        match self.records.get(path) {
+            Some(record)
+                if record.explored.get(&0) == Some(&false) && record.nothing == Some(true) =>
+            {
+                Choice::Nothing
+            }
+            _ => Choice::Explore(0),
+        }
+    }
+
+    /// Returns passes required to describe recorded parts.
+    fn tasks(&self) -> Vec<(Vec<u32>, Choice)> {
+        let mut tasks = Vec::new();
+        for (path, record) in &self.records {
+            if record.nothing.is_none() {
+                tasks.push((path.clone(), Choice::Nothing));
+            }
+            if !record.explored.contains_key(&0) {
+                tasks.push((path.clone(), Choice::Explore(0)));
+            }
+            match record.raw {
+                Raw::Enum(_, variants) => {
+                    for index in 1..variants.len() as u32 {
+                        tasks.push((path.clone(), Choice::Explore(index)));
+                    }
+                }
+                Raw::Struct(_, fields) | Raw::StructPayload(fields) => {
+                    for index in 0..fields.len() as u32 {
+                        if !record.omitted.contains_key(&index) {
+                            tasks.push((path.clone(), Choice::Omit(index)));
+                        }
+                    }
+                    for index in 1..fields.len() as u32 {
+                        let mut field = path.clone();
+                        field.push(index);
+                        if !self.records.contains_key(&field) {
+                            tasks.push((path.clone(), Choice::Explore(index)));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        tasks
+    }
+
+    fn build(&self, path: &mut Vec<u32>) -> Shape {
+        let record = match self.records.get(&*path) {
+            None => {
+                return Shape {
+                    kind: Kind::Unknown,
+                    nothing: false,
+                }
+            }
+            Some(record) => record,
+        };
+
+        let kind = match record.raw {
+            Raw::Leaf(ref kind) => kind.clone(),
+            Raw::Option => Kind::Option(Box::new(self.child(path, 0))),
+            Raw::Seq => Kind::Seq(Box::new(self.child(path, 0))),
+            Raw::Tuple(len) => Kind::Tuple(self.children(path, len)),
+            Raw::Map => Kind::Map {
+                key: Box::new(self.child(path, 0)),
+                value: Box::new(self.child(path, 1)),
+            },
+            Raw::UnitStruct(name) => Kind::UnitStruct { name },
+            Raw::NewtypeStruct(name) => Kind::NewtypeStruct {
+                name,
+                inner: Box::new(self.child(path, 0)),
+            },
+            Raw::TupleStruct(name, len) => Kind::TupleStruct {
+                name,
+                fields: self.children(path, len),
+            },
+            Raw::Struct(name, fields) => Kind::Struct {
+                name,
+                fields: self.fields(path, fields),
+            },
+            Raw::Enum(name, variants) => Kind::Enum {
+                name,
+                variants: variants
+                    .iter()
+                    .enumerate()
+                    .map(|(index, name)| {
+                        path.push(index as u32);
+                        let variant = self.variant(path, name);
+                        path.pop();
+                        variant
+                    })
+                    .collect(),
+            },
+            Raw::Recursive(name) => Kind::Recursive { name },
+            Raw::UnitPayload
+            | Raw::NewtypePayload
+            | Raw::TuplePayload(_)
+            | Raw::StructPayload(_) => Kind::Unknown,
+        };
+
+        Shape {
+            kind,
+            nothing: record.nothing == Some(true),
+        }
+    }
+
+    fn child(&self, path: &mut Vec<u32>, index: u32) -> Shape {
+        path.push(index);
+        let shape = self.build(path);
+        path.pop();
+        shape
+    }
+
+    fn children(&self, path: &mut Vec<u32>, len: usize) -> Vec<Shape> {
+        (0..len as u32)
+            .map(|index| self.child(path, index))
+            .collect()
+    }
+
+    fn fields(&self, path: &mut Vec<u32>, fields: &'static [&'static str]) -> Vec<Field> {
+        let omitted = &self.records[&*path].omitted;
+        fields
+            .iter()
+            .enumerate()
+            .map(|(index, name)| Field {
+                name,
+                shape: self.child(path, index as u32),
+                omittable: omitted.get(&(index as u32)) == Some(&true),
+            })
+            .collect()
+    }
+
+    fn variant(&self, path: &mut Vec<u32>, name: &'static str) -> Variant {
+        let record = match self.records.get(&*path) {
+            None => {
+                return Variant {
+                    name,
+                    nothing: false,
+                    payload: Payload::Unknown,
+                }
+            }
+            Some(record) => record,
+        };
+
+        let payload = match record.raw {
+            Raw::UnitPayload => Payload::Unit,
+            Raw::NewtypePayload => Payload::Newtype(Box::new(self.child(path, 0))),
+            Raw::TuplePayload(len) => Payload::Tuple(self.children(path, len)),
+            Raw::StructPayload(fields) => Payload::Struct(self.fields(path, fields)),
+            _ => Payload::Unknown,
+        };
+
+        Variant {
+            name,
+            nothing: record.nothing == Some(true),
+            payload,
+        }
+    }
+}
+
+/// Deserializer that records requests at some path and decides how to answer them.
+struct Tracer<'a> {
+    state: &'a RefCell<State>,
+    path: Vec<u32>,
+    names: Vec<&'static str>,
+    /// Struct field to leave out, set for `Choice::Omit` only.
+    omit: Option<u32>,
+}
+
+impl<'a> Tracer<'a> {
+    fn child(&self, index: u32) -> Tracer<'a> {
+        let mut path = self.path.clone();
+        path.push(index);
+        Tracer {
+            state: self.state,
+            path,
+            names: self.names.clone(),
+            omit: None,
+        }
+    }
+
+    /// Records the request and answers it according to the choice for the path.
+    fn trace<I, T>(
+        mut self,
+        raw: Raw,
+        input: I,
+        nothing: impl FnOnce(I) -> Result<T, NothingDeserializeError>,
+        explore: impl FnOnce(&Self, u32, I) -> Result<T, NothingDeserializeError>,
+    ) -> Result<T, NothingDeserializeError> {
+        let choice = {
+            let mut state = self.state.borrow_mut();
+            state
+                .records
+                .entry(self.path.clone())
+                .or_insert_with(|| Record {
+                    raw,
+                    explored: BTreeMap::new(),
+                    omitted: BTreeMap::new(),
+                    nothing: None,
+                });
+            state.choice(&self.path)
+        };
+
+        let result = match choice {
+            Choice::Nothing => nothing(input),
+            Choice::Explore(index) => explore(&self, index, input),
+            Choice::Omit(index) => {
+                self.omit = Some(index);
+                explore(&self, 0, input)
+            }
+        };
+
+        let mut state = self.state.borrow_mut();
+        let record = state.records.get_mut(&self.path).unwrap();
+        match choice {
+            Choice::Nothing => record.nothing = Some(result.is_ok()),
+            Choice::Explore(index) => {
+                record.explored.insert(index, result.is_ok());
+            }
+            Choice::Omit(index) => {
+                record.omitted.insert(index, result.is_ok());
+            }
+        }
+
+        result
+    }
+
+    /// Deserializes child part with the seed.
+    ///
+    /// Seed may reject the value after the child deserializer succeeds,
+    /// like types that validate deserialized strings do.
+    /// Such failure is recorded for the child.
+    fn deserialize_child<'de, T>(
+        &self,
+        index: u32,
+        seed: T,
+    ) -> Result<T::Value, NothingDeserializeError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let child = self.child(index);
+        let path = child.path.clone();
+        let choice = self.state.borrow().choice(&path);
+
+        let result = seed.deserialize(child);
+
+        if result.is_err() {
+            let mut state = self.state.borrow_mut();
+            if let Some(record) = state.records.get_mut(&path) {
+                match choice {
+                    Choice::Nothing => record.nothing = Some(false),
+                    Choice::Explore(index) => {
+                        record.explored.insert(index, false);
+                    }
+                    Choice::Omit(index) => {
+                        record.omitted.insert(index, false);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Enters named struct or enum.
+    /// Fails if the name is already entered.
+    fn enter(mut self, name: &'static str) -> Result<Self, Self> {
+        if self.names.contains(&name) {
+            Err(self)
+        } else {
+            self.names.push(name);
+            Ok(self)
+        }
+    }
+
+    /// Records recursive part and deserializes it from `Nothing`.
+    fn recursive<'de, V>(
+        self,
+        name: &'static str,
+        visitor: V,
+        nothing: impl FnOnce(V) -> Result<V::Value, NothingDeserializeError>,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        let result = nothing(visitor);
+
+        let mut state = self.state.borrow_mut();
+        let record = state.records.entry(self.path).or_insert_with(|| Record {
+            raw: Raw::Recursive(name),
+            explored: BTreeMap::new(),
+            omitted: BTreeMap::new(),
+            nothing: None,
+        });
+        record.nothing = Some(result.is_ok());
+        record.explored.insert(0, result.is_ok());
+
+        result
+    }
+}
+
+fn something<T>(
+    result: Result<T, crate::SomethingDeserializeError>,
+) -> Result<T, NothingDeserializeError> {
+    result.map_err(|_| NothingDeserializeError)
+}
+
+fn explore_struct<'de, V>(
+    tracer: &Tracer,
+    start: u32,
+    fields: &'static [&'static str],
+    visitor: V,
+) -> Result<V::Value, NothingDeserializeError>
+where
+    V: Visitor<'de>,
+{
+    if let Some(omit) = tracer.omit {
+        visitor.visit_map(TraceFields {
+            tracer,
+            fields,
+            start: omit as usize + 1,
+            count: 0,
+            len: fields.len().saturating_sub(1),
+        })
+    } else if start == 0 {
+        visitor.visit_seq(TraceSeq {
+            tracer,
+            index: 0,
+            len: fields.len() as u32,
+        })
+    } else {
+        visitor.visit_map(TraceFields {
+            tracer,
+            fields,
+            start: start as usize,
+            count: 0,
+            len: fields.len(),
+        })
+    }
+}
+
+macro_rules! trace_leaf {
+    ($($method:ident => $kind:ident,)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, NothingDeserializeError>
+            where
+                V: Visitor<'de>,
+            {
+                self.trace(
+                    Raw::Leaf(Kind::$kind),
+                    visitor,
+                    |visitor| Nothing.$method(visitor),
+                    |_, _, visitor| something(Something.$method(visitor)),
+                )
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Tracer<'_> {
+    type Error = NothingDeserializeError;
+
+    trace_leaf! {
+        deserialize_any => Any,
+        deserialize_bool => Bool,
+        deserialize_i8 => I8,
+        deserialize_i16 => I16,
+        deserialize_i32 => I32,
+        deserialize_i64 => I64,
+        deserialize_i128 => I128,
+        deserialize_u8 => U8,
+        deserialize_u16 => U16,
+        deserialize_u32 => U32,
+        deserialize_u64 => U64,
+        deserialize_u128 => U128,
+        deserialize_f32 => F32,
+        deserialize_f64 => F64,
+        deserialize_char => Char,
+        deserialize_str => Str,
+        deserialize_string => Str,
+        deserialize_bytes => Bytes,
+        deserialize_byte_buf => Bytes,
+        deserialize_identifier => Identifier,
+        deserialize_ignored_any => Any,
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        self.trace(
+            Raw::Leaf(Kind::Unit),
+            visitor,
+            |visitor| Nothing.deserialize_unit(visitor),
+            |_, _, visitor| Nothing.deserialize_unit(visitor),
+        )
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        self.trace(
+            Raw::Option,
+            visitor,
+            |visitor| Nothing.deserialize_option(visitor),
+            |tracer, _, visitor| visitor.visit_some(tracer.child(0)),
+        )
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        self.trace(
+            Raw::UnitStruct(name),
+            visitor,
+            |visitor| Nothing.deserialize_unit_struct(name, visitor),
+            |_, _, visitor| visitor.visit_unit(),
+        )
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        let nothing = |visitor| Nothing.deserialize_newtype_struct(name, visitor);
+        match self.enter(name) {
+            Err(tracer) => tracer.recursive(name, visitor, nothing),
+            Ok(tracer) => tracer.trace(
+                Raw::NewtypeStruct(name),
+                visitor,
+                nothing,
+                |tracer, _, visitor| visitor.visit_newtype_struct(tracer.child(0)),
+            ),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        self.trace(
+            Raw::Seq,
+            visitor,
+            |visitor| Nothing.deserialize_seq(visitor),
+            |tracer, _, visitor| {
+                visitor.visit_seq(TraceSeq {
+                    tracer,
+                    index: 0,
+                    len: 1,
+                })
+            },
+        )
+    }
+
+    fn deserialize_tuple<V>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        self.trace(
+            Raw::Tuple(len),
+            visitor,
+            |visitor| Nothing.deserialize_tuple(len, visitor),
+            |tracer, _, visitor| {
+                visitor.visit_seq(TraceSeq {
+                    tracer,
+                    index: 0,
+                    len: len as u32,
+                })
+            },
+        )
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        let nothing = |visitor| Nothing.deserialize_tuple_struct(name, len, visitor);
+        match self.enter(name) {
+            Err(tracer) => tracer.recursive(name, visitor, nothing),
+            Ok(tracer) => tracer.trace(
+                Raw::TupleStruct(name, len),
+                visitor,
+                nothing,
+                |tracer, _, visitor| {
+                    visitor.visit_seq(TraceSeq {
+                        tracer,
+                        index: 0,
+                        len: len as u32,
+                    })
+                },
+            ),
+        }
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        self.trace(
+            Raw::Map,
+            visitor,
+            |visitor| Nothing.deserialize_map(visitor),
+            |tracer, _, visitor| visitor.visit_map(TraceMap { tracer, index: 0 }),
+        )
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        let nothing = |visitor| Nothing.deserialize_struct(name, fields, visitor);
+        match self.enter(name) {
+            Err(tracer) => tracer.recursive(name, visitor, nothing),
+            Ok(tracer) => tracer.trace(
+                Raw::Struct(name, fields),
+                visitor,
+                nothing,
+                |tracer, start, visitor| explore_struct(tracer, start, fields, visitor),
+            ),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        let nothing = |visitor| Nothing.deserialize_enum(name, variants, visitor);
+        match self.enter(name) {
+            Err(tracer) => tracer.recursive(name, visitor, nothing),
+            Ok(tracer) => tracer.trace(
+                Raw::Enum(name, variants),
+                visitor,
+                nothing,
+                |tracer, variant, visitor| {
+                    if variant as usize >= variants.len() {
+                        return Err(NothingDeserializeError);
+                    }
+                    visitor.visit_enum(TraceEnum { tracer, variant })
+                },
+            ),
+        }
+    }
+}
+
+struct TraceSeq<'a, 'b> {
+    tracer: &'a Tracer<'b>,
+    index: u32,
+    len: u32,
+}
+
+impl<'de> SeqAccess<'de> for TraceSeq<'_, '_> {
+    type Error = NothingDeserializeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, NothingDeserializeError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.index == self.len {
+            return Ok(None);
+        }
+        let index = self.index;
+        self.index += 1;
+        self.tracer.deserialize_child(index, seed).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some((self.len - self.index) as usize)
+    }
+}
+
+/// Map with single entry, key is child 0 and value is child 1.
+struct TraceMap<'a, 'b> {
+    tracer: &'a Tracer<'b>,
+    index: u32,
+}
+
+impl<'de> MapAccess<'de> for TraceMap<'_, '_> {
+    type Error = NothingDeserializeError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, NothingDeserializeError>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.index > 0 {
+            return Ok(None);
+        }
+        self.index += 1;
+        self.tracer.deserialize_child(0, seed).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.tracer.deserialize_child(1, seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(1 - self.index as usize)
+    }
+}
+
+/// Struct fields as a map, `len` fields starting with field `start`.
+struct TraceFields<'a, 'b> {
+    tracer: &'a Tracer<'b>,
+    fields: &'static [&'static str],
+    start: usize,
+    count: usize,
+    len: usize,
+}
+
+impl TraceFields<'_, '_> {
+    fn current(&self) -> usize {
+        (self.start + self.count) % self.fields.len()
+    }
+}
+
+impl<'de> MapAccess<'de> for TraceFields<'_, '_> {
+    type Error = NothingDeserializeError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, NothingDeserializeError>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.count == self.len {
+            return Ok(None);
+        }
+        let key: StrDeserializer<NothingDeserializeError> =
+            self.fields[self.current()].into_deserializer();
+        seed.deserialize(key).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let index = self.current() as u32;
+        self.count += 1;
+        self.tracer.deserialize_child(index, seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.count)
+    }
+}
+
+struct TraceEnum<'a, 'b> {
+    tracer: &'a Tracer<'b>,
+    variant: u32,
+}
+
+impl<'de, 'b> EnumAccess<'de> for TraceEnum<'_, 'b> {
+    type Error = NothingDeserializeError;
+    type Variant = TracePayload<'b>;
+
+    fn variant_seed<V>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, TracePayload<'b>), NothingDeserializeError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant: U32Deserializer<NothingDeserializeError> = self.variant.into_deserializer();
+        let value = seed.deserialize(variant)?;
+        Ok((value, TracePayload(self.tracer.child(self.variant))))
+    }
+}
+
+/// Payload of enum variant.
+struct TracePayload<'a>(Tracer<'a>);
+
+impl<'de> VariantAccess<'de> for TracePayload<'_> {
+    type Error = NothingDeserializeError;
+
+    fn unit_variant(self) -> Result<(), NothingDeserializeError> {
+        self.0.trace(
+            Raw::UnitPayload,
+            (),
+            |()| VariantAccess::<'de>::unit_variant(Nothing),
+            |_, _, ()| Ok(()),
+        )
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, NothingDeserializeError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.0.trace(
+            Raw::NewtypePayload,
+            seed,
+            |seed| Nothing.newtype_variant_seed(seed),
+            |tracer, _, seed| tracer.deserialize_child(0, seed),
+        )
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.trace(
+            Raw::TuplePayload(len),
+            visitor,
+            |visitor| Nothing.tuple_variant(len, visitor),
+            |tracer, _, visitor| {
+                visitor.visit_seq(TraceSeq {
+                    tracer,
+                    index: 0,
+                    len: len as u32,
+                })
+            },
+        )
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.trace(
+            Raw::StructPayload(fields),
+            visitor,
+            |visitor| Nothing.struct_variant(fields, visitor),
+            |tracer, start, visitor| explore_struct(tracer, start, fields, visitor),
+        )
+    }
+}
+
+#[test]
+fn test_describe() {
+    use std::{collections::HashMap, num::NonZeroU32, string::String};
+
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    enum Mode {
+        Off,
+        Fixed(u32),
+        Range {
+            #[serde(default)]
+            min: u32,
+            max: NonZeroU32,
+        },
+    }
+
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct Config {
+        name: String,
+        limit: Option<NonZeroU32>,
+        #[serde(default)]
+        tags: Vec<String>,
+        env: HashMap<String, (u8, bool)>,
+        mode: Mode,
+    }
+
+    let shape = describe::<Config>();
+    assert!(shape.nothing);
+
+    let fields = match shape.kind {
+        Kind::Struct { name, fields } => {
+            assert_eq!(name, "Config");
+            fields
+        }
+        kind => panic!("unexpected {:?}", kind),
+    };
+
+    let names: Vec<_> = fields.iter().map(|field| field.name).collect();
+    assert_eq!(names, ["name", "limit", "tags", "env", "mode"]);
+
+    // Missing optional fields are `None`, other fields need `#[serde(default)]`.
+    let omittable: Vec<_> = fields.iter().map(|field| field.omittable).collect();
+    assert_eq!(omittable, [false, true, true, false, false]);
+
+    assert_eq!(fields[0].shape.kind, Kind::Str);
+
+    assert_eq!(
+        fields[1].shape.kind,
+        Kind::Option(Box::new(Shape {
+            kind: Kind::U32,
+            nothing: false,
+        }))
+    );
+
+    assert_eq!(
+        fields[2].shape.kind,
+        Kind::Seq(Box::new(Shape {
+            kind: Kind::Str,
+            nothing: true,
+        }))
+    );
+
+    match &fields[3].shape.kind {
+        Kind::Map { key, value } => {
+            assert_eq!(key.kind, Kind::Str);
+            assert!(matches!(&value.kind, Kind::Tuple(elements) if elements.len() == 2));
+        }
+        kind => panic!("unexpected {:?}", kind),
+    }
+
+    match &fields[4].shape.kind {
+        Kind::Enum { name, variants } => {
+            assert_eq!(*name, "Mode");
+            assert_eq!(variants.len(), 3);
+            assert_eq!(variants[0].payload, Payload::Unit);
+            assert!(variants[0].nothing);
+            assert!(
+                matches!(&variants[1].payload, Payload::Newtype(inner) if inner.kind == Kind::U32)
+            );
+            assert!(variants[1].nothing);
+            match &variants[2].payload {
+                Payload::Struct(fields) => {
+                    assert_eq!(fields[0].name, "min");
+                    assert!(fields[0].shape.nothing);
+                    assert!(fields[0].omittable);
+                    assert_eq!(fields[1].name, "max");
+                    assert!(!fields[1].shape.nothing);
+                    assert!(!fields[1].omittable);
+                }
+                payload => panic!("unexpected {:?}", payload),
+            }
+            assert!(!variants[2].nothing);
+        }
+        kind => panic!("unexpected {:?}", kind),
+    }
+}
+
+#[test]
+fn test_describe_recursive() {
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct Node {
+        value: u32,
+        children: Vec<Node>,
+    }
+
+    let shape = describe::<Node>();
+    match shape.kind {
+        Kind::Struct { fields, .. } => match &fields[1].shape.kind {
+            Kind::Seq(element) => {
+                assert_eq!(element.kind, Kind::Recursive { name: "Node" });
+                assert!(element.nothing);
+            }
+            kind => panic!("unexpected {:?}", kind),
+        },
+        kind => panic!("unexpected {:?}", kind),
+    }
+}
+
+#[test]
+fn test_describe_unconstructible() {
+    use std::string::String;
+
+    /// Accepts only strings with scheme.
+    #[derive(Debug)]
+    struct Url;
+
+    impl<'de> Deserialize<'de> for Url {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let s = String::deserialize(deserializer)?;
+            if s.contains("://") {
+                Ok(Url)
+            } else {
+                Err(serde::de::Error::custom("not a url"))
+            }
+        }
+    }
+
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct Remote {
+        url: Url,
+        retries: u8,
+    }
+
+    let shape = describe::<Remote>();
+    assert!(!shape.nothing);
+    match shape.kind {
+        Kind::Struct { fields, .. } => {
+            assert_eq!(fields[0].shape.kind, Kind::Str);
+            assert!(!fields[0].shape.nothing);
+            assert_eq!(fields[1].shape.kind, Kind::U8);
+            assert!(fields[1].shape.nothing);
+        }
+        kind => panic!("unexpected {:?}", kind),
+    }
+}
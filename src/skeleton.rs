@@ -0,0 +1,651 @@
+//!
+//! Config skeletons and references generated from type shape.
+//!
+//! [`skeleton`] returns a value that serializes as nothing value of the type
+//! through any `Serializer`.
+//! The value is produced by `Serialize` implementation of the type itself,
+//! so custom implementations and attributes like `skip_serializing_if` and `flatten` are respected.
+//! Parts that cannot be nothing, like `NonZeroU32` or validated strings,
+//! are filled with minimal values to construct the value
+//! and serialized as placeholder strings in angle brackets, like `"<u32>"`,
+//! instead of failing the whole generation.
+//! If the type cannot be constructed even that way,
+//! skeleton is serialized from its shape with every field present.
+//!
+//! [`Skeleton::reference`] renders Markdown table of all fields instead.
+//!
+
+use std::{fmt::Write, format, string::String, vec::Vec};
+
+use serde::{
+    de::IntoDeserializer,
+    ser::{
+        SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+        SerializeTupleStruct, SerializeTupleVariant,
+    },
+    Deserialize, Serialize, Serializer,
+};
+
+use crate::{
+    shape::{describe, Field, Kind, Payload, Shape},
+    value::{to_value, Value, ValueError},
+    Nothing,
+};
+
+/// Skeleton of a type.
+///
+/// Serializes as nothing value of the type with placeholders
+/// for parts that cannot be nothing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Skeleton {
+    shape: Shape,
+    value: Value,
+}
+
+/// Returns skeleton of the type.
+///
+/// ```
+/// # use serde_nothing::skeleton::skeleton;
+/// #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+/// struct Config {
+///     name: String,
+///     port: std::num::NonZeroU16,
+///     tags: Vec<String>,
+///     #[serde(skip_serializing_if = "Option::is_none")]
+///     timeout: Option<f32>,
+/// }
+///
+/// let json = serde_json::to_string(&skeleton::<Config>()).unwrap();
+/// assert_eq!(json, r#"{"name":"","port":"<u16>","tags":[]}"#);
+/// ```
+pub fn skeleton<'de, T>() -> Skeleton
+where
+    T: Serialize + Deserialize<'de>,
+{
+    let shape = describe::<T>();
+    let value = match fill::<T>(&shape).and_then(|value| to_value::<_, ValueError>(&value).ok()) {
+        Some(mut value) => {
+            mark(&mut value, &shape);
+            value
+        }
+        None => to_value::<_, ValueError>(&Node {
+            shape: &shape,
+            fill: false,
+        })
+        .unwrap_or(Value::Unit),
+    };
+    Skeleton { shape, value }
+}
+
+/// Returns nothing value of the type,
+/// or value with parts that cannot be nothing filled with minimal values.
+fn fill<'de, T>(shape: &Shape) -> Option<T>
+where
+    T: Deserialize<'de>,
+{
+    if let Ok(value) = T::deserialize(Nothing) {
+        return Some(value);
+    }
+
+    let filled: Value = to_value::<_, ValueError>(&Node { shape, fill: true }).ok()?;
+    T::deserialize(IntoDeserializer::<ValueError>::into_deserializer(filled)).ok()
+}
+
+/// Replaces primitives that cannot be nothing with placeholders.
+fn mark(value: &mut Value, shape: &Shape) {
+    if !shape.nothing && is_leaf(&shape.kind) {
+        if is_primitive(value) {
+            *value = Value::String(placeholder(&shape.kind));
+        }
+        return;
+    }
+
+    match (&shape.kind, value) {
+        (Kind::Option(inner), Value::Some(value)) => mark(value, inner),
+        (Kind::NewtypeStruct { inner, .. }, Value::Newtype(value)) => mark(value, inner),
+        (Kind::NewtypeStruct { inner, .. }, value) => mark(value, inner),
+        (Kind::Seq(element), Value::Seq(values) | Value::Tuple(values)) => {
+            values.iter_mut().for_each(|value| mark(value, element))
+        }
+        (
+            Kind::Tuple(elements)
+            | Kind::TupleStruct {
+                fields: elements, ..
+            },
+            Value::Seq(values) | Value::Tuple(values),
+        ) => mark_elements(values, elements),
+        (Kind::Map { value: shape, .. }, Value::Map(entries)) => {
+            entries.iter_mut().for_each(|(_, value)| mark(value, shape))
+        }
+        (Kind::Struct { fields, .. }, value) => mark_fields(value, fields),
+        (Kind::Enum { variants, .. }, Value::Variant(name, payload)) => {
+            let variant = match variants.iter().find(|variant| variant.name == *name) {
+                Some(variant) => variant,
+                None => return,
+            };
+            match (&variant.payload, &mut **payload) {
+                (Payload::Newtype(inner), payload) => mark(payload, inner),
+                (Payload::Tuple(elements), Value::Tuple(values)) => mark_elements(values, elements),
+                (Payload::Struct(fields), payload) => mark_fields(payload, fields),
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+fn mark_elements(values: &mut [Value], elements: &[Shape]) {
+    for (value, element) in values.iter_mut().zip(elements) {
+        mark(value, element);
+    }
+}
+
+fn mark_fields(value: &mut Value, fields: &[Field]) {
+    let mark_field = |name: &str, value: &mut Value| {
+        if let Some(field) = fields.iter().find(|field| field.name == name) {
+            mark(value, &field.shape);
+        }
+    };
+
+    match value {
+        Value::Struct(entries) => {
+            for (name, value) in entries {
+                mark_field(name, value);
+            }
+        }
+        Value::Map(entries) => {
+            for (name, value) in entries {
+                if let Value::String(name) = name {
+                    mark_field(name, value);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_primitive(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::Bool(_)
+            | Value::I64(_)
+            | Value::U64(_)
+            | Value::I128(_)
+            | Value::U128(_)
+            | Value::F64(_)
+            | Value::Char(_)
+            | Value::String(_)
+            | Value::Bytes(_)
+            | Value::Unit
+    )
+}
+
+impl Skeleton {
+    /// Returns shape of the type.
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    /// Renders Markdown reference table with field path, kind,
+    /// nothing value and whether the field can be omitted.
+    pub fn reference(&self) -> String {
+        let mut rows = Vec::new();
+        collect_rows(&self.shape, &mut String::new(), &mut rows);
+
+        let mut table = String::from("| Path | Kind | Nothing value | Omittable |\n");
+        table.push_str("|------|------|---------------|-----------|\n");
+        for row in rows {
+            let _ = writeln!(
+                table,
+                "| `{}` | {} | {} | {} |",
+                row.path,
+                row.kind,
+                row.nothing,
+                if row.omittable { "yes" } else { "no" }
+            );
+        }
+        table
+    }
+}
+
+impl Serialize for Skeleton {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.value.serialize(serializer)
+    }
+}
+
+/// Serializes part of the shape.
+/// Parts that cannot be nothing are serialized as minimal values with `fill`
+/// and as placeholders otherwise.
+struct Node<'a> {
+    shape: &'a Shape,
+    fill: bool,
+}
+
+impl<'a> Node<'a> {
+    fn child(&self, shape: &'a Shape) -> Self {
+        Node {
+            shape,
+            fill: self.fill,
+        }
+    }
+}
+
+impl Serialize for Node<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let shape = self.shape;
+        if !shape.nothing && is_leaf(&shape.kind) {
+            if self.fill {
+                return serialize_minimal(&shape.kind, serializer);
+            }
+            return serializer.serialize_str(&placeholder(&shape.kind));
+        }
+
+        match &shape.kind {
+            Kind::Bool => serializer.serialize_bool(false),
+            Kind::I8 => serializer.serialize_i8(0),
+            Kind::I16 => serializer.serialize_i16(0),
+            Kind::I32 => serializer.serialize_i32(0),
+            Kind::I64 => serializer.serialize_i64(0),
+            Kind::I128 => serializer.serialize_i128(0),
+            Kind::U8 => serializer.serialize_u8(0),
+            Kind::U16 => serializer.serialize_u16(0),
+            Kind::U32 => serializer.serialize_u32(0),
+            Kind::U64 => serializer.serialize_u64(0),
+            Kind::U128 => serializer.serialize_u128(0),
+            Kind::F32 => serializer.serialize_f32(0.0),
+            Kind::F64 => serializer.serialize_f64(0.0),
+            Kind::Char => serializer.serialize_char('\0'),
+            Kind::Str => serializer.serialize_str(""),
+            Kind::Bytes => serializer.serialize_bytes(&[]),
+            Kind::Unit | Kind::Identifier | Kind::Any => serializer.serialize_unit(),
+            Kind::Recursive { .. } | Kind::Unknown => {
+                serializer.serialize_str(&placeholder(&shape.kind))
+            }
+            Kind::Option(inner) => {
+                if shape.nothing {
+                    serializer.serialize_none()
+                } else {
+                    serializer.serialize_some(&self.child(inner))
+                }
+            }
+            Kind::Seq(element) => {
+                if shape.nothing {
+                    serializer.serialize_seq(Some(0))?.end()
+                } else {
+                    let mut seq = serializer.serialize_seq(Some(1))?;
+                    seq.serialize_element(&self.child(element))?;
+                    seq.end()
+                }
+            }
+            Kind::Map { key, value } => {
+                if shape.nothing {
+                    serializer.serialize_map(Some(0))?.end()
+                } else {
+                    let mut map = serializer.serialize_map(Some(1))?;
+                    map.serialize_entry(&self.child(key), &self.child(value))?;
+                    map.end()
+                }
+            }
+            Kind::Tuple(elements) => {
+                let mut tuple = serializer.serialize_tuple(elements.len())?;
+                for element in elements {
+                    tuple.serialize_element(&self.child(element))?;
+                }
+                tuple.end()
+            }
+            Kind::UnitStruct { name } => serializer.serialize_unit_struct(name),
+            Kind::NewtypeStruct { name, inner } => {
+                serializer.serialize_newtype_struct(name, &self.child(inner))
+            }
+            Kind::TupleStruct { name, fields } => {
+                let mut tuple = serializer.serialize_tuple_struct(name, fields.len())?;
+                for field in fields {
+                    tuple.serialize_field(&self.child(field))?;
+                }
+                tuple.end()
+            }
+            Kind::Struct { name, fields } => {
+                let mut s = serializer.serialize_struct(name, fields.len())?;
+                for field in fields {
+                    s.serialize_field(field.name, &self.child(&field.shape))?;
+                }
+                s.end()
+            }
+            Kind::Enum { name, variants } => {
+                // Prefer the first variant that can be nothing.
+                let index = variants
+                    .iter()
+                    .position(|variant| variant.nothing)
+                    .unwrap_or(0);
+                let variant = match variants.get(index) {
+                    None => return serializer.serialize_str(&placeholder(&shape.kind)),
+                    Some(variant) => variant,
+                };
+                let index = index as u32;
+
+                match &variant.payload {
+                    Payload::Unit | Payload::Unknown => {
+                        serializer.serialize_unit_variant(name, index, variant.name)
+                    }
+                    Payload::Newtype(inner) => serializer.serialize_newtype_variant(
+                        name,
+                        index,
+                        variant.name,
+                        &self.child(inner),
+                    ),
+                    Payload::Tuple(elements) => {
+                        let mut tuple = serializer.serialize_tuple_variant(
+                            name,
+                            index,
+                            variant.name,
+                            elements.len(),
+                        )?;
+                        for element in elements {
+                            tuple.serialize_field(&self.child(element))?;
+                        }
+                        tuple.end()
+                    }
+                    Payload::Struct(fields) => {
+                        let mut s = serializer.serialize_struct_variant(
+                            name,
+                            index,
+                            variant.name,
+                            fields.len(),
+                        )?;
+                        for field in fields {
+                            s.serialize_field(field.name, &self.child(&field.shape))?;
+                        }
+                        s.end()
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Serializes minimal value of the kind that is not nothing,
+/// same as `Something` deserializer produces.
+fn serialize_minimal<S>(kind: &Kind, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match kind {
+        Kind::Bool => serializer.serialize_bool(true),
+        Kind::I8 => serializer.serialize_i8(1),
+        Kind::I16 => serializer.serialize_i16(1),
+        Kind::I32 => serializer.serialize_i32(1),
+        Kind::I64 => serializer.serialize_i64(1),
+        Kind::I128 => serializer.serialize_i128(1),
+        Kind::U8 => serializer.serialize_u8(1),
+        Kind::U16 => serializer.serialize_u16(1),
+        Kind::U32 => serializer.serialize_u32(1),
+        Kind::U64 => serializer.serialize_u64(1),
+        Kind::U128 => serializer.serialize_u128(1),
+        Kind::F32 => serializer.serialize_f32(1.0),
+        Kind::F64 => serializer.serialize_f64(1.0),
+        Kind::Char => serializer.serialize_char('a'),
+        Kind::Str => serializer.serialize_str("a"),
+        Kind::Bytes => serializer.serialize_bytes(b"a"),
+        _ => serializer.serialize_unit(),
+    }
+}
+
+fn is_leaf(kind: &Kind) -> bool {
+    matches!(
+        kind,
+        Kind::Bool
+            | Kind::I8
+            | Kind::I16
+            | Kind::I32
+            | Kind::I64
+            | Kind::I128
+            | Kind::U8
+            | Kind::U16
+            | Kind::U32
+            | Kind::U64
+            | Kind::U128
+            | Kind::F32
+            | Kind::F64
+            | Kind::Char
+            | Kind::Str
+            | Kind::Bytes
+            | Kind::Unit
+            | Kind::UnitStruct { .. }
+            | Kind::Identifier
+            | Kind::Any
+    )
+}
+
+fn placeholder(kind: &Kind) -> String {
+    format!("<{}>", kind_name(kind))
+}
+
+fn kind_name(kind: &Kind) -> String {
+    match kind {
+        Kind::Bool => "bool".into(),
+        Kind::I8 => "i8".into(),
+        Kind::I16 => "i16".into(),
+        Kind::I32 => "i32".into(),
+        Kind::I64 => "i64".into(),
+        Kind::I128 => "i128".into(),
+        Kind::U8 => "u8".into(),
+        Kind::U16 => "u16".into(),
+        Kind::U32 => "u32".into(),
+        Kind::U64 => "u64".into(),
+        Kind::U128 => "u128".into(),
+        Kind::F32 => "f32".into(),
+        Kind::F64 => "f64".into(),
+        Kind::Char => "char".into(),
+        Kind::Str => "string".into(),
+        Kind::Bytes => "bytes".into(),
+        Kind::Unit => "unit".into(),
+        Kind::Option(inner) => format!("optional {}", kind_name(&inner.kind)),
+        Kind::Seq(element) => format!("list of {}", kind_name(&element.kind)),
+        Kind::Tuple(elements) => format!("tuple of {}", elements.len()),
+        Kind::Map { key, value } => format!(
+            "map of {} to {}",
+            kind_name(&key.kind),
+            kind_name(&value.kind)
+        ),
+        Kind::UnitStruct { name }
+        | Kind::NewtypeStruct { name, .. }
+        | Kind::TupleStruct { name, .. }
+        | Kind::Struct { name, .. }
+        | Kind::Recursive { name } => (*name).into(),
+        Kind::Enum { name, variants } => {
+            let names: Vec<_> = variants.iter().map(|variant| variant.name).collect();
+            format!("{} ({})", name, names.join(", "))
+        }
+        Kind::Identifier => "identifier".into(),
+        Kind::Any => "any".into(),
+        Kind::Unknown => "unknown".into(),
+    }
+}
+
+fn nothing_value(shape: &Shape) -> String {
+    if !shape.nothing {
+        return "-".into();
+    }
+
+    match &shape.kind {
+        Kind::Bool => "`false`".into(),
+        Kind::I8
+        | Kind::I16
+        | Kind::I32
+        | Kind::I64
+        | Kind::I128
+        | Kind::U8
+        | Kind::U16
+        | Kind::U32
+        | Kind::U64
+        | Kind::U128 => "`0`".into(),
+        Kind::F32 | Kind::F64 => "`0.0`".into(),
+        Kind::Char => "`'\\0'`".into(),
+        Kind::Str => "`\"\"`".into(),
+        Kind::Bytes | Kind::Seq(_) => "`[]`".into(),
+        Kind::Map { .. } => "`{}`".into(),
+        Kind::Option(_) => "none".into(),
+        Kind::NewtypeStruct { inner, .. } => nothing_value(inner),
+        Kind::Enum { variants, .. } => match variants.iter().find(|variant| variant.nothing) {
+            Some(variant) => format!("`{}`", variant.name),
+            None => "-".into(),
+        },
+        _ => "nothing".into(),
+    }
+}
+
+struct Row {
+    path: String,
+    kind: String,
+    nothing: String,
+    omittable: bool,
+}
+
+fn push_row(shape: &Shape, omittable: bool, path: &str, rows: &mut Vec<Row>) {
+    rows.push(Row {
+        path: path.into(),
+        kind: kind_name(&shape.kind),
+        nothing: nothing_value(shape),
+        omittable,
+    });
+}
+
+fn collect_rows(shape: &Shape, path: &mut String, rows: &mut Vec<Row>) {
+    match &shape.kind {
+        Kind::Option(inner) | Kind::NewtypeStruct { inner, .. } => collect_rows(inner, path, rows),
+        Kind::Seq(element) => collect_child(element, false, "[]", path, rows),
+        Kind::Map { value, .. } => collect_child(value, false, "{}", path, rows),
+        Kind::Tuple(elements)
+        | Kind::TupleStruct {
+            fields: elements, ..
+        } => collect_elements(elements, path, rows),
+        Kind::Struct { fields, .. } => collect_fields(fields, path, rows),
+        Kind::Enum { variants, .. } => {
+            for variant in variants {
+                let len = path.len();
+                path.push_str("::");
+                path.push_str(variant.name);
+                match &variant.payload {
+                    Payload::Newtype(inner) => collect_rows(inner, path, rows),
+                    Payload::Tuple(elements) => collect_elements(elements, path, rows),
+                    Payload::Struct(fields) => collect_fields(fields, path, rows),
+                    Payload::Unit | Payload::Unknown => {}
+                }
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Only struct fields can be omitted, see [`Field::omittable`].
+fn collect_child(
+    shape: &Shape,
+    omittable: bool,
+    segment: &str,
+    path: &mut String,
+    rows: &mut Vec<Row>,
+) {
+    let len = path.len();
+    path.push_str(segment);
+    push_row(shape, omittable, path, rows);
+    collect_rows(shape, path, rows);
+    path.truncate(len);
+}
+
+fn collect_elements(elements: &[Shape], path: &mut String, rows: &mut Vec<Row>) {
+    for (index, element) in elements.iter().enumerate() {
+        collect_child(element, false, &format!("[{}]", index), path, rows);
+    }
+}
+
+fn collect_fields(fields: &[Field], path: &mut String, rows: &mut Vec<Row>) {
+    for field in fields {
+        let segment = format!(".{}", field.name);
+        collect_child(&field.shape, field.omittable, &segment, path, rows);
+    }
+}
+
+#[test]
+fn test_skeleton() {
+    use std::num::NonZeroU32;
+
+    #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    enum Mode {
+        Off,
+        On { level: u8 },
+    }
+
+    #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct Limits {
+        max: NonZeroU32,
+        #[serde(default)]
+        soft: u32,
+    }
+
+    #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct Config {
+        name: String,
+        limits: Limits,
+        mode: Mode,
+        #[serde(default)]
+        pair: (bool, char),
+    }
+
+    let skeleton = skeleton::<Config>();
+
+    let json = serde_json::to_string(&skeleton).unwrap();
+    assert_eq!(
+        json,
+        r#"{"name":"","limits":{"max":"<u32>","soft":0},"mode":"Off","pair":[false,"\u0000"]}"#
+    );
+
+    assert_eq!(
+        skeleton.reference(),
+        "\
+| Path | Kind | Nothing value | Omittable |
+|------|------|---------------|-----------|
+| `.name` | string | `\"\"` | no |
+| `.limits` | Limits | - | no |
+| `.limits.max` | u32 | - | no |
+| `.limits.soft` | u32 | `0` | yes |
+| `.mode` | Mode (Off, On) | `Off` | no |
+| `.mode::On.level` | u8 | `0` | no |
+| `.pair` | tuple of 2 | nothing | yes |
+| `.pair[0]` | bool | `false` | no |
+| `.pair[1]` | char | `'\\0'` | no |
+"
+    );
+
+    #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct Extra {
+        #[serde(default)]
+        level: u8,
+    }
+
+    #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct Service {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        comment: Option<String>,
+        #[serde(flatten)]
+        extra: Extra,
+        #[serde(default, rename = "service-name")]
+        name: String,
+    }
+
+    // Written by `Serialize` implementation of the type, not by its shape.
+    let json = serde_json::to_string(&self::skeleton::<Service>()).unwrap();
+    assert_eq!(json, r#"{"level":0,"service-name":""}"#);
+}
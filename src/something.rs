@@ -0,0 +1,511 @@
+use core::fmt;
+
+use serde::{
+    de::{DeserializeSeed, EnumAccess, Expected, MapAccess, Unexpected, VariantAccess, Visitor},
+    Deserializer,
+};
+
+use crate::{de::Few, Nothing, Something};
+
+/// Map with single entry where both key and value are produced by `Something`.
+struct OneEntry {
+    key: bool,
+    value: bool,
+}
+
+impl<'de> MapAccess<'de> for OneEntry {
+    type Error = SomethingDeserializeError;
+
+    #[inline]
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, SomethingDeserializeError>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.key {
+            self.key = false;
+            seed.deserialize(Something).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    #[inline]
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        if self.value {
+            self.value = false;
+            seed.deserialize(Something)
+        } else {
+            Err(SomethingDeserializeError)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.key as usize)
+    }
+}
+
+/// Error type for `Something` deserializer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SomethingDeserializeError;
+
+impl fmt::Display for SomethingDeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Specific value expected")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SomethingDeserializeError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+impl serde::de::Error for SomethingDeserializeError {
+    #[inline]
+    fn custom<T>(_: T) -> Self {
+        SomethingDeserializeError
+    }
+
+    #[inline]
+    fn invalid_type(_: Unexpected, _: &dyn Expected) -> Self {
+        SomethingDeserializeError
+    }
+
+    #[inline]
+    fn invalid_value(_: Unexpected, _: &dyn Expected) -> Self {
+        SomethingDeserializeError
+    }
+
+    #[inline]
+    fn invalid_length(_: usize, _: &dyn Expected) -> Self {
+        SomethingDeserializeError
+    }
+
+    #[inline]
+    fn unknown_variant(_: &str, _: &'static [&'static str]) -> Self {
+        SomethingDeserializeError
+    }
+
+    #[inline]
+    fn unknown_field(_: &str, _: &'static [&'static str]) -> Self {
+        SomethingDeserializeError
+    }
+
+    #[inline]
+    fn missing_field(_: &'static str) -> Self {
+        SomethingDeserializeError
+    }
+
+    #[inline]
+    fn duplicate_field(_: &'static str) -> Self {
+        SomethingDeserializeError
+    }
+}
+
+impl<'de> Deserializer<'de> for Something {
+    type Error = SomethingDeserializeError;
+
+    #[inline]
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u8(1)
+    }
+
+    #[inline]
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_bool(true)
+    }
+
+    #[inline]
+    fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_i8(1)
+    }
+
+    #[inline]
+    fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_i16(1)
+    }
+
+    #[inline]
+    fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_i32(1)
+    }
+
+    #[inline]
+    fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_i64(1)
+    }
+
+    #[inline]
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u8(1)
+    }
+
+    #[inline]
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u16(1)
+    }
+
+    #[inline]
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u32(1)
+    }
+
+    #[inline]
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u64(1)
+    }
+
+    #[inline]
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_i128(1)
+    }
+
+    #[inline]
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u128(1)
+    }
+
+    #[inline]
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_f32(1.0)
+    }
+
+    #[inline]
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_f64(1.0)
+    }
+
+    #[inline]
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_char('a')
+    }
+
+    #[inline]
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_str("a")
+    }
+
+    #[inline]
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str("a")
+    }
+
+    #[inline]
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_bytes(b"a")
+    }
+
+    #[inline]
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_bytes(b"a")
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    #[inline]
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    #[inline]
+    fn deserialize_unit_struct<V>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    #[inline]
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Few { de: self, len: 1 })
+    }
+
+    #[inline]
+    fn deserialize_tuple<V>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Few { de: self, len })
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Few { de: self, len })
+    }
+
+    #[inline]
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(OneEntry {
+            key: true,
+            value: true,
+        })
+    }
+
+    #[inline]
+    fn deserialize_struct<V>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Few {
+            de: self,
+            len: fields.len(),
+        })
+    }
+
+    #[inline]
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(self)
+    }
+
+    #[inline]
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_u8(0)
+    }
+
+    #[inline]
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+impl<'de> EnumAccess<'de> for Something {
+    type Error = SomethingDeserializeError;
+
+    type Variant = Self;
+
+    /// Picks the first variant, same as `Nothing` does.
+    #[inline]
+    fn variant_seed<V>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), SomethingDeserializeError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = seed
+            .deserialize(Nothing)
+            .map_err(|_| SomethingDeserializeError)?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Something {
+    type Error = SomethingDeserializeError;
+
+    #[inline]
+    fn unit_variant(self) -> Result<(), SomethingDeserializeError> {
+        Ok(())
+    }
+
+    #[inline]
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, SomethingDeserializeError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    #[inline]
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Few { de: self, len })
+    }
+
+    #[inline]
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SomethingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Few {
+            de: self,
+            len: fields.len(),
+        })
+    }
+}
+
+#[test]
+fn test_primitives() {
+    use serde::Deserialize;
+
+    assert_eq!(Deserialize::deserialize(Something), Ok(1u32));
+    assert_eq!(Deserialize::deserialize(Something), Ok(1.0f32));
+    assert_eq!(Deserialize::deserialize(Something), Ok(true));
+    assert_eq!(Deserialize::deserialize(Something), Ok("a"));
+    assert_eq!(Deserialize::deserialize(Something), Ok(Some('a')));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_collections() {
+    use std::collections::BTreeMap;
+
+    assert_eq!(crate::from_something(), Some(vec![1u8]));
+    assert_eq!(
+        crate::from_something(),
+        Some(BTreeMap::from([(String::from("a"), 1u8)]))
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_struct() {
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    enum Kind {
+        Plain(u32),
+        Other,
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    struct Struct {
+        number: core::num::NonZeroU32,
+        string: String,
+        list: Vec<Option<bool>>,
+        kind: Kind,
+        #[serde(default, skip_serializing_if = "crate::is_nothing")]
+        skipped: u32,
+    }
+
+    let value: Struct = crate::from_something().unwrap();
+    assert_eq!(
+        value,
+        Struct {
+            number: core::num::NonZeroU32::new(1).unwrap(),
+            string: "a".into(),
+            list: vec![Some(true)],
+            kind: Kind::Plain(1),
+            skipped: 1,
+        }
+    );
+    assert!(!crate::is_nothing(&value));
+}
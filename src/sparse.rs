@@ -0,0 +1,332 @@
+//!
+//! Collections without nothing elements.
+//!
+//! Submodules are meant for `#[serde(with = "...")]` on collection fields.
+//! `serialize` leaves out sequence elements and map entries with nothing values
+//! and `deserialize` drops them as well,
+//! so `{"a": 0, "b": 5}` becomes `{"b": 5}`.
+//! To keep nothing values read from input
+//! use only `#[serde(serialize_with = "...::serialize")]`.
+//!
+//! Map keys are kept as is, only values are checked.
+//!
+//! ```
+//! # use std::collections::BTreeMap;
+//! #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+//! struct Counters {
+//!     #[serde(with = "serde_nothing::sparse::btree_map")]
+//!     hits: BTreeMap<String, u32>,
+//! }
+//!
+//! let counters: Counters = serde_json::from_str(r#"{"hits": {"a": 0, "b": 5}}"#).unwrap();
+//! assert_eq!(counters.hits.len(), 1);
+//!
+//! let mut counters = counters;
+//! counters.hits.insert("c".into(), 0);
+//! assert_eq!(serde_json::to_string(&counters).unwrap(), r#"{"hits":{"b":5}}"#);
+//! ```
+//!
+
+use core::{fmt, marker::PhantomData};
+use std::vec::Vec;
+
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::is_nothing;
+
+fn serialize_seq<'a, T, I, S>(elements: I, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize + 'a,
+    I: IntoIterator<Item = &'a T>,
+    S: Serializer,
+{
+    // Collected first, so that length is known to formats that need it.
+    let elements: Vec<&T> = elements.into_iter().filter(|v| !is_nothing(*v)).collect();
+    serializer.collect_seq(elements)
+}
+
+fn serialize_map<'a, K, V, I, S>(entries: I, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Serialize + 'a,
+    V: Serialize + 'a,
+    I: IntoIterator<Item = (&'a K, &'a V)>,
+    S: Serializer,
+{
+    let entries: Vec<(&K, &V)> = entries
+        .into_iter()
+        .filter(|(_, v)| !is_nothing(*v))
+        .collect();
+    let mut map = serializer.serialize_map(Some(entries.len()))?;
+    for (key, value) in entries {
+        map.serialize_entry(key, value)?;
+    }
+    map.end()
+}
+
+fn deserialize_seq<'de, C, T, D>(deserializer: D) -> Result<C, D::Error>
+where
+    C: Default + Extend<T>,
+    T: Serialize + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_seq(SparseSeqVisitor(PhantomData))
+}
+
+fn deserialize_map<'de, C, K, V, D>(deserializer: D) -> Result<C, D::Error>
+where
+    C: Default + Extend<(K, V)>,
+    K: Deserialize<'de>,
+    V: Serialize + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_map(SparseMapVisitor(PhantomData))
+}
+
+struct SparseSeqVisitor<C, T>(PhantomData<(C, T)>);
+
+impl<'de, C, T> Visitor<'de> for SparseSeqVisitor<C, T>
+where
+    C: Default + Extend<T>,
+    T: Serialize + Deserialize<'de>,
+{
+    type Value = C;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<C, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut collection = C::default();
+        while let Some(element) = seq.next_element::<T>()? {
+            if !is_nothing(&element) {
+                collection.extend(Some(element));
+            }
+        }
+        Ok(collection)
+    }
+}
+
+struct SparseMapVisitor<C, K, V>(PhantomData<(C, K, V)>);
+
+impl<'de, C, K, V> Visitor<'de> for SparseMapVisitor<C, K, V>
+where
+    C: Default + Extend<(K, V)>,
+    K: Deserialize<'de>,
+    V: Serialize + Deserialize<'de>,
+{
+    type Value = C;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<C, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut collection = C::default();
+        while let Some((key, value)) = map.next_entry::<K, V>()? {
+            if !is_nothing(&value) {
+                collection.extend(Some((key, value)));
+            }
+        }
+        Ok(collection)
+    }
+}
+
+/// `Vec` without nothing elements.
+pub mod vec {
+    use std::vec::Vec;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes elements that are not nothing.
+    #[inline]
+    pub fn serialize<T, S>(value: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        super::serialize_seq(value, serializer)
+    }
+
+    /// Deserializes elements and drops nothing ones.
+    #[inline]
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        T: Serialize + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        super::deserialize_seq(deserializer)
+    }
+}
+
+/// `HashMap` without entries with nothing values.
+pub mod hash_map {
+    use core::hash::{BuildHasher, Hash};
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes entries with values that are not nothing.
+    #[inline]
+    pub fn serialize<K, V, H, S>(value: &HashMap<K, V, H>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        super::serialize_map(value, serializer)
+    }
+
+    /// Deserializes entries and drops ones with nothing values.
+    #[inline]
+    pub fn deserialize<'de, K, V, H, D>(deserializer: D) -> Result<HashMap<K, V, H>, D::Error>
+    where
+        K: Eq + Hash + Deserialize<'de>,
+        V: Serialize + Deserialize<'de>,
+        H: BuildHasher + Default,
+        D: Deserializer<'de>,
+    {
+        super::deserialize_map(deserializer)
+    }
+}
+
+/// `BTreeMap` without entries with nothing values.
+pub mod btree_map {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes entries with values that are not nothing.
+    #[inline]
+    pub fn serialize<K, V, S>(value: &BTreeMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        super::serialize_map(value, serializer)
+    }
+
+    /// Deserializes entries and drops ones with nothing values.
+    #[inline]
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<BTreeMap<K, V>, D::Error>
+    where
+        K: Ord + Deserialize<'de>,
+        V: Serialize + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        super::deserialize_map(deserializer)
+    }
+}
+
+/// `IndexMap` without entries with nothing values.
+///
+/// Requires `indexmap` feature.
+#[cfg(feature = "indexmap")]
+pub mod index_map {
+    use core::hash::{BuildHasher, Hash};
+
+    use indexmap::IndexMap;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serializes entries with values that are not nothing, keeping the order.
+    #[inline]
+    pub fn serialize<K, V, H, S>(
+        value: &IndexMap<K, V, H>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        super::serialize_map(value, serializer)
+    }
+
+    /// Deserializes entries and drops ones with nothing values.
+    #[inline]
+    pub fn deserialize<'de, K, V, H, D>(deserializer: D) -> Result<IndexMap<K, V, H>, D::Error>
+    where
+        K: Eq + Hash + Deserialize<'de>,
+        V: Serialize + Deserialize<'de>,
+        H: BuildHasher + Default,
+        D: Deserializer<'de>,
+    {
+        super::deserialize_map(deserializer)
+    }
+}
+
+#[test]
+fn test_sparse() {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        string::String,
+    };
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    struct Config {
+        #[serde(with = "vec")]
+        levels: Vec<Option<u32>>,
+        #[serde(with = "hash_map")]
+        flags: HashMap<String, bool>,
+        #[serde(with = "btree_map")]
+        counters: BTreeMap<u32, Vec<u32>>,
+    }
+
+    let config: Config = serde_json::from_str(
+        r#"{
+            "levels": [null, 0, null, 3],
+            "flags": {"a": false, "b": true},
+            "counters": {"1": [], "2": [0]}
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(config.levels, [Some(0), Some(3)]);
+    assert_eq!(config.flags.len(), 1);
+    assert!(config.flags["b"]);
+    assert_eq!(config.counters.len(), 1);
+    assert_eq!(config.counters[&2], [0]);
+
+    let config = Config {
+        levels: vec![None, Some(1), None],
+        flags: HashMap::from([("a".into(), false)]),
+        counters: BTreeMap::from([(1, vec![]), (2, vec![]), (3, vec![1])]),
+    };
+    assert_eq!(
+        serde_json::to_string(&config).unwrap(),
+        r#"{"levels":[1],"flags":{},"counters":{"3":[1]}}"#
+    );
+}
+
+#[cfg(feature = "indexmap")]
+#[test]
+fn test_sparse_index_map() {
+    use indexmap::IndexMap;
+    use std::string::String;
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    struct Features {
+        #[serde(with = "index_map")]
+        enabled: IndexMap<String, u8>,
+    }
+
+    let features: Features =
+        serde_json::from_str(r#"{"enabled": {"z": 1, "y": 0, "x": 2}}"#).unwrap();
+    assert_eq!(features.enabled.keys().collect::<Vec<_>>(), ["z", "x"]);
+
+    let mut features = features;
+    features.enabled.insert("w".into(), 0);
+    assert_eq!(
+        serde_json::to_string(&features).unwrap(),
+        r#"{"enabled":{"z":1,"x":2}}"#
+    );
+}
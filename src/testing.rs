@@ -0,0 +1,708 @@
+//!
+//! Checks of skip-and-fill laws for types used with this crate.
+//!
+//! Skip-and-fill pattern relies on few laws that `Serialize` and `Deserialize`
+//! implementations of a type must uphold together.
+//! [`check_type`] verifies them and panics with a message pointing to the offending path.
+//! [`try_check_type`] returns the failure instead.
+//!
+//! Requires `testing` feature.
+//!
+
+use std::{any::type_name, cell::Cell, fmt, string::String};
+
+use serde::{
+    de::{
+        value::U32Deserializer, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer,
+        VariantAccess, Visitor,
+    },
+    Deserializer, Serialize,
+};
+use serde_json::Value;
+
+use crate::{de::Few, is_nothing, registry, Nothing, NothingDeserializeError, Something};
+
+/// Law checked by [`try_check_type`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Law {
+    /// `from_nothing` must succeed.
+    FromNothing,
+
+    /// `is_nothing(&from_nothing())` must hold.
+    IsNothing,
+
+    /// Nothing value must be skipped on serialization
+    /// and filled back equal on deserialization.
+    SkipAndFill,
+
+    /// Nothing value must round-trip through non-self-describing format.
+    NonSelfDescribing,
+
+    /// Non-nothing value must not be skipped and must round-trip.
+    Something,
+
+    /// Enum variants that are nothing must be equal to the nothing value,
+    /// otherwise skipping them loses data.
+    EnumVariants,
+}
+
+impl fmt::Display for Law {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Law::FromNothing => "from_nothing",
+            Law::IsNothing => "is_nothing(from_nothing)",
+            Law::SkipAndFill => "skip-and-fill round-trip",
+            Law::NonSelfDescribing => "non-self-describing round-trip",
+            Law::Something => "non-nothing round-trip",
+            Law::EnumVariants => "nothing enum variants",
+        })
+    }
+}
+
+/// Failure of a law check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckError {
+    /// Law that failed.
+    pub law: Law,
+
+    /// Path to the offending part of the value, like `.field[1].inner`.
+    /// Empty for the value as a whole.
+    pub path: String,
+
+    /// Failure description.
+    pub details: String,
+}
+
+impl CheckError {
+    fn new(law: Law, path: String, details: impl fmt::Display) -> Self {
+        CheckError {
+            law,
+            path,
+            details: details.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{} law failed: {}", self.law, self.details)
+        } else {
+            write!(
+                f,
+                "{} law failed at `{}`: {}",
+                self.law, self.path, self.details
+            )
+        }
+    }
+}
+
+impl std::error::Error for CheckError {}
+
+/// Checks skip-and-fill laws for the type.
+///
+/// # Panics
+///
+/// Panics with description of the failed law and offending path.
+#[track_caller]
+pub fn check_type<T>()
+where
+    T: Serialize + DeserializeOwned,
+{
+    if let Err(err) = try_check_type::<T>() {
+        panic!("`{}`: {}", type_name::<T>(), err);
+    }
+}
+
+/// Checks skip-and-fill laws for the type.
+///
+/// * `from_nothing::<T>()` succeeds and the value `is_nothing`.
+/// * Nothing value in a field with `skip_serializing_if = "is_nothing"` is skipped,
+///   and deserializing with `default` filled from `Nothing` yields equal value.
+///   Checked with `serde_json`.
+/// * Nothing value round-trips through `bincode`, a non-self-describing format.
+/// * Value from `Something` deserializer is not skipped unless it is nothing
+///   and round-trips with `serde_json`.
+/// * Enum variants that are nothing are equal to the nothing value,
+///   which is always the first variant.
+///   Checked for every enum reachable from the nothing value, not only the top-level one.
+///
+/// Values are compared by their `serde_json` representation.
+pub fn try_check_type<T>() -> Result<(), CheckError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let nothing = T::deserialize(Nothing).map_err(|_| {
+        CheckError::new(Law::FromNothing, String::new(), "type has no nothing value")
+    })?;
+
+    let nothing_json = to_json(&nothing, Law::IsNothing)?;
+    if !is_nothing(&nothing) {
+        return Err(CheckError::new(
+            Law::IsNothing,
+            first_something(&nothing_json),
+            "value created from nothing is not nothing",
+        ));
+    }
+
+    check_skip_and_fill(&nothing, &nothing_json)?;
+    check_non_self_describing::<T>(&nothing_json)?;
+
+    if let Ok(something) = T::deserialize(Something) {
+        check_something(&something)?;
+    }
+
+    check_enum_variants::<T>(&nothing_json)
+}
+
+#[derive(serde_derive::Serialize)]
+struct Skip<'a, T: Serialize> {
+    #[serde(skip_serializing_if = "is_nothing")]
+    value: &'a T,
+}
+
+#[derive(serde_derive::Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+struct Fill<T> {
+    #[serde(default = "fill")]
+    value: T,
+}
+
+fn fill<T>() -> T
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(Nothing).expect("Checked before filling")
+}
+
+fn to_json<T>(value: &T, law: Law) -> Result<Value, CheckError>
+where
+    T: Serialize,
+{
+    serde_json::to_value(value).map_err(|err| CheckError::new(law, String::new(), err))
+}
+
+fn check_skip_and_fill<T>(nothing: &T, nothing_json: &Value) -> Result<(), CheckError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let law = Law::SkipAndFill;
+
+    let json = serde_json::to_string(&Skip { value: nothing })
+        .map_err(|err| CheckError::new(law, String::new(), err))?;
+    if json != "{}" {
+        return Err(CheckError::new(
+            law,
+            String::new(),
+            format_args!("nothing value is not skipped: `{}`", json),
+        ));
+    }
+
+    let filled: Fill<T> =
+        serde_json::from_str(&json).map_err(|err| CheckError::new(law, String::new(), err))?;
+    let filled_json = to_json(&filled.value, law)?;
+    compare(law, nothing_json, &filled_json)
+}
+
+fn check_non_self_describing<T>(nothing_json: &Value) -> Result<(), CheckError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let law = Law::NonSelfDescribing;
+
+    let nothing = fill::<T>();
+    let bytes =
+        bincode::serialize(&nothing).map_err(|err| CheckError::new(law, String::new(), err))?;
+    let decoded: T =
+        bincode::deserialize(&bytes).map_err(|err| CheckError::new(law, String::new(), err))?;
+
+    let decoded_json = to_json(&decoded, law)?;
+    compare(law, nothing_json, &decoded_json)
+}
+
+fn check_something<T>(something: &T) -> Result<(), CheckError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let law = Law::Something;
+
+    let something_json = to_json(something, law)?;
+    let json = serde_json::to_string(&Skip { value: something })
+        .map_err(|err| CheckError::new(law, String::new(), err))?;
+
+    if json == "{}" {
+        if is_nothing(something) {
+            return Ok(());
+        }
+        return Err(CheckError::new(
+            law,
+            String::new(),
+            "non-nothing value is skipped",
+        ));
+    }
+
+    let filled: Fill<T> =
+        serde_json::from_str(&json).map_err(|err| CheckError::new(law, String::new(), err))?;
+    let filled_json = to_json(&filled.value, law)?;
+    compare(law, &something_json, &filled_json)
+}
+
+fn check_enum_variants<T>(nothing_json: &Value) -> Result<(), CheckError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let law = Law::EnumVariants;
+
+    let seen = Cell::new(0);
+    let variants = Cell::new(&[][..]);
+
+    for target in 0.. {
+        for index in 1.. {
+            seen.set(0);
+            variants.set(&[]);
+            let result = T::deserialize(NthVariant {
+                target,
+                index,
+                seen: &seen,
+                variants: &variants,
+            });
+
+            if seen.get() <= target {
+                // No more enums in the value.
+                return Ok(());
+            }
+
+            let variants = variants.get();
+            if index as usize >= variants.len() {
+                break;
+            }
+
+            if let Ok(value) = result {
+                if is_nothing(&value) {
+                    let value_json = to_json(&value, law)?;
+                    if let Some(path) = first_difference(nothing_json, &value_json) {
+                        return Err(CheckError::new(
+                            law,
+                            path,
+                            format_args!(
+                                "variant `{}` is nothing and would be filled back as `{}`",
+                                variants[index as usize], variants[0],
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn compare(law: Law, expected: &Value, actual: &Value) -> Result<(), CheckError> {
+    match first_difference(expected, actual) {
+        None => Ok(()),
+        Some(path) => Err(CheckError::new(
+            law,
+            path,
+            format_args!("expected `{}`, got `{}`", expected, actual),
+        )),
+    }
+}
+
+/// Returns path to the deepest part of the value that is not nothing.
+fn first_something(value: &Value) -> String {
+    fn walk(value: &Value, path: &mut String) {
+        let json_nothing = |value: &Value| match value {
+            Value::Null => true,
+            Value::Bool(v) => !v,
+            Value::Number(v) => v.as_f64() == Some(0.0),
+            Value::String(v) => v.is_empty(),
+            Value::Array(v) => v.is_empty(),
+            Value::Object(v) => v.is_empty(),
+        };
+
+        match value {
+            Value::Array(elements) => {
+                if let Some((index, element)) =
+                    elements.iter().enumerate().find(|(_, e)| !json_nothing(e))
+                {
+                    path.push_str(&format!("[{}]", index));
+                    walk(element, path);
+                }
+            }
+            Value::Object(fields) => {
+                if let Some((name, field)) = fields.iter().find(|(_, f)| !json_nothing(f)) {
+                    path.push('.');
+                    path.push_str(name);
+                    walk(field, path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut path = String::new();
+    walk(value, &mut path);
+    path
+}
+
+/// Returns path to the first difference between two values.
+fn first_difference(expected: &Value, actual: &Value) -> Option<String> {
+    fn walk(expected: &Value, actual: &Value, path: &mut String) -> bool {
+        match (expected, actual) {
+            (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+                for (index, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+                    let len = path.len();
+                    path.push_str(&format!("[{}]", index));
+                    if walk(expected, actual, path) {
+                        return true;
+                    }
+                    path.truncate(len);
+                }
+                false
+            }
+            (Value::Object(expected), Value::Object(actual)) => {
+                for (name, expected_field) in expected {
+                    let len = path.len();
+                    path.push('.');
+                    path.push_str(name);
+                    match actual.get(name) {
+                        None => return true,
+                        Some(actual_field) => {
+                            if walk(expected_field, actual_field, path) {
+                                return true;
+                            }
+                        }
+                    }
+                    path.truncate(len);
+                }
+                if let Some(name) = actual.keys().find(|name| !expected.contains_key(*name)) {
+                    path.push('.');
+                    path.push_str(name);
+                    return true;
+                }
+                false
+            }
+            _ => expected != actual,
+        }
+    }
+
+    let mut path = String::new();
+    if walk(expected, actual, &mut path) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Deserializer that picks variant `index` of enum number `target`,
+/// counting enums in order of deserialization, and records its variants.
+/// Other enums get first variant, same as with `Nothing`.
+/// Everything else is deserialized as from `Nothing`.
+#[derive(Clone, Copy)]
+struct NthVariant<'a> {
+    target: usize,
+    index: u32,
+    seen: &'a Cell<usize>,
+    variants: &'a Cell<&'static [&'static str]>,
+}
+
+macro_rules! forward_to_nothing {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, NothingDeserializeError>
+            where
+                V: Visitor<'de>,
+            {
+                Nothing.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for NthVariant<'_> {
+    type Error = NothingDeserializeError;
+
+    forward_to_nothing! {
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_map(),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        if registry::lookup(name).is_some() {
+            return Nothing.deserialize_newtype_struct(name, visitor);
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Few { de: self, len })
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        if registry::lookup(name).is_some() {
+            return Nothing.deserialize_tuple_struct(name, len, visitor);
+        }
+        visitor.visit_seq(Few { de: self, len })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        if registry::lookup(name).is_some() {
+            return Nothing.deserialize_struct(name, fields, visitor);
+        }
+        visitor.visit_seq(Few {
+            de: self,
+            len: fields.len(),
+        })
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        if registry::lookup(name).is_some() {
+            return Nothing.deserialize_enum(name, variants, visitor);
+        }
+
+        let number = self.seen.get();
+        self.seen.set(number + 1);
+
+        if number != self.target {
+            return visitor.visit_enum(Pick { de: self, index: 0 });
+        }
+
+        self.variants.set(variants);
+        if self.index as usize >= variants.len() {
+            return Err(NothingDeserializeError);
+        }
+        visitor.visit_enum(Pick {
+            de: self,
+            index: self.index,
+        })
+    }
+}
+
+/// Variant picked by `NthVariant`.
+struct Pick<'a> {
+    de: NthVariant<'a>,
+    index: u32,
+}
+
+impl<'de, 'a> EnumAccess<'de> for Pick<'a> {
+    type Error = NothingDeserializeError;
+    type Variant = NthVariant<'a>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, NthVariant<'a>), NothingDeserializeError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let index: U32Deserializer<NothingDeserializeError> = self.index.into_deserializer();
+        let value = seed.deserialize(index)?;
+        Ok((value, self.de))
+    }
+}
+
+impl<'de> VariantAccess<'de> for NthVariant<'_> {
+    type Error = NothingDeserializeError;
+
+    fn unit_variant(self) -> Result<(), NothingDeserializeError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, NothingDeserializeError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Few { de: self, len })
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, NothingDeserializeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Few {
+            de: self,
+            len: fields.len(),
+        })
+    }
+}
+
+#[test]
+fn test_check_type() {
+    #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+    struct Config {
+        name: String,
+        list: Vec<u32>,
+        pair: (u8, Option<bool>),
+    }
+
+    check_type::<u32>();
+    check_type::<String>();
+    check_type::<Option<Vec<String>>>();
+    check_type::<Config>();
+}
+
+#[test]
+fn test_no_nothing() {
+    let err = try_check_type::<core::num::NonZeroU32>().unwrap_err();
+    assert_eq!(err.law, Law::FromNothing);
+}
+
+#[test]
+fn test_enum_variants() {
+    #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+    enum Mode {
+        Fast,
+        Slow,
+    }
+
+    #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+    enum Payload {
+        Empty,
+        Data(u32),
+    }
+
+    #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+    enum Single {
+        Data(u32),
+    }
+
+    let err = try_check_type::<Mode>().unwrap_err();
+    assert_eq!(err.law, Law::EnumVariants);
+    assert!(err.details.contains("`Slow`"));
+
+    // `Data(0)` is nothing too.
+    let err = try_check_type::<Payload>().unwrap_err();
+    assert_eq!(err.law, Law::EnumVariants);
+    assert!(err.details.contains("`Data`"));
+
+    check_type::<Single>();
+
+    #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+    struct Config {
+        name: String,
+        modes: (u32, Mode),
+    }
+
+    #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+    enum Nested {
+        Inner(Mode),
+    }
+
+    let err = try_check_type::<Config>().unwrap_err();
+    assert_eq!(err.law, Law::EnumVariants);
+    assert_eq!(err.path, ".modes[1]");
+    assert!(err.details.contains("`Slow`"));
+
+    let err = try_check_type::<Nested>().unwrap_err();
+    assert_eq!(err.law, Law::EnumVariants);
+    assert_eq!(err.path, ".Inner");
+}
+
+#[test]
+fn test_path() {
+    #[derive(serde_derive::Serialize)]
+    struct Inner {
+        value: u32,
+    }
+
+    #[derive(serde_derive::Serialize)]
+    struct Outer {
+        first: u32,
+        inner: Inner,
+    }
+
+    /// Deserializes as `Outer` with non-nothing value deep inside.
+    struct Broken;
+
+    impl Serialize for Broken {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            Outer {
+                first: 0,
+                inner: Inner { value: 7 },
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> serde::Deserialize<'de> for Broken {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            serde::de::IgnoredAny::deserialize(deserializer)?;
+            Ok(Broken)
+        }
+    }
+
+    let err = try_check_type::<Broken>().unwrap_err();
+    assert_eq!(err.law, Law::IsNothing);
+    assert_eq!(err.path, ".inner.value");
+}
@@ -0,0 +1,393 @@
+//!
+//! Nothing in TOML documents.
+//!
+//! [`prune_nothing`] removes keys with nothing values from `toml_edit` documents:
+//! `false`, zero numbers, empty strings, empty arrays and tables.
+//! Tables are pruned bottom up, so tables left without keys are removed too.
+//! Comments, ordering and formatting of everything else are preserved,
+//! so documents edited by hand stay familiar.
+//!
+//! [`prune_nothing_for`] is guided by a type:
+//! only fields that the type fills back from `Nothing` when missing are removed.
+//!
+//! Requires `toml_edit` feature.
+//!
+
+use std::vec::Vec;
+
+use ::toml_edit::{Array, Item, Table, Value};
+use serde::Deserialize;
+
+use crate::shape::{describe, Kind, Payload, Shape};
+
+/// Removes keys with nothing values from the table or document.
+///
+/// Array elements are pruned but never removed,
+/// since that would shift positions of other elements.
+///
+/// ```
+/// let mut doc: toml_edit::DocumentMut = r#"
+/// ## Service name.
+/// name = "web"
+/// ## Not used yet.
+/// port = 0
+///
+/// [limits]
+/// cpu = 0.0
+/// tags = []
+/// "#
+/// .parse()
+/// .unwrap();
+///
+/// serde_nothing::toml::prune_nothing(&mut doc);
+/// assert_eq!(doc.to_string(), "\n# Service name.\nname = \"web\"\n");
+/// ```
+pub fn prune_nothing(table: &mut Table) {
+    prune_table(table);
+}
+
+/// Removes keys of fields that `T` fills back from `Nothing`.
+///
+/// Shape of `T` is discovered with [`describe`].
+/// Only nothing values of fields that `T` accepts missing are removed,
+/// see [`Field::omittable`](crate::shape::Field::omittable).
+/// Keys that are not fields of `T`, map entries, `Some` values of optional fields,
+/// required fields and fields that cannot be nothing are kept.
+///
+/// Values filled in for missing fields are not checked,
+/// so a field with `#[serde(default = "...")]` that is not nothing
+/// should not be written as nothing in the document.
+///
+/// ```
+/// #[derive(serde_derive::Deserialize)]
+/// struct Config {
+///     #[serde(default)]
+///     port: u16,
+///     name: String,
+///     retries: Option<u32>,
+///     threads: std::num::NonZeroU32,
+/// }
+///
+/// let mut doc: toml_edit::DocumentMut =
+///     "port = 0\nname = \"\"\nretries = 0\nthreads = 4\nextra = 0\n"
+///         .parse()
+///         .unwrap();
+///
+/// serde_nothing::toml::prune_nothing_for::<Config>(&mut doc);
+/// assert_eq!(doc.to_string(), "name = \"\"\nretries = 0\nthreads = 4\nextra = 0\n");
+/// ```
+pub fn prune_nothing_for<'de, T>(table: &mut Table)
+where
+    T: Deserialize<'de>,
+{
+    let shape = describe::<T>();
+    prune_table_as(table, &shape);
+}
+
+fn prune_item(item: &mut Item) {
+    match item {
+        Item::Value(value) => prune_value(value),
+        Item::Table(table) => prune_table(table),
+        Item::ArrayOfTables(tables) => tables.iter_mut().for_each(prune_table),
+        Item::None => {}
+    }
+}
+
+fn prune_value(value: &mut Value) {
+    match value {
+        Value::Array(array) => array.iter_mut().for_each(prune_value),
+        Value::InlineTable(table) => {
+            table.iter_mut().for_each(|(_, value)| prune_value(value));
+            table.retain(|_, value| !is_nothing_value(value));
+        }
+        _ => {}
+    }
+}
+
+fn prune_table(table: &mut Table) {
+    table.iter_mut().for_each(|(_, item)| prune_item(item));
+    table.retain(|_, item| !is_nothing_item(item));
+}
+
+fn is_nothing_item(item: &Item) -> bool {
+    match item {
+        Item::None => true,
+        Item::Value(value) => is_nothing_value(value),
+        Item::Table(table) => table.is_empty(),
+        Item::ArrayOfTables(tables) => tables.is_empty(),
+    }
+}
+
+fn is_nothing_value(value: &Value) -> bool {
+    match value {
+        Value::String(v) => v.value().is_empty(),
+        Value::Integer(v) => *v.value() == 0,
+        Value::Float(v) => *v.value() == 0.0,
+        Value::Boolean(v) => !*v.value(),
+        Value::Datetime(_) => false,
+        Value::Array(v) => v.is_empty(),
+        Value::InlineTable(v) => v.is_empty(),
+    }
+}
+
+/// Skips wrappers that are transparent in TOML.
+fn inner(shape: &Shape) -> &Shape {
+    match &shape.kind {
+        Kind::NewtypeStruct { inner: shape, .. } => inner(shape),
+        _ => shape,
+    }
+}
+
+fn prune_table_as(table: &mut Table, shape: &Shape) {
+    let mut removable = Vec::new();
+    for (key, item) in table.iter_mut() {
+        if let Some((field, omittable)) = field_shape(shape, key.get()) {
+            prune_item_as(item, field);
+            if omittable && field.nothing && is_nothing_item_as(item, field) {
+                removable.push(key.get().to_owned());
+            }
+        }
+    }
+    table.retain(|key, _| !removable.iter().any(|r| r == key));
+}
+
+fn prune_inline_table_as(table: &mut ::toml_edit::InlineTable, shape: &Shape) {
+    let mut removable = Vec::new();
+    for (key, value) in table.iter_mut() {
+        if let Some((field, omittable)) = field_shape(shape, key.get()) {
+            prune_value_as(value, field);
+            if omittable && field.nothing && is_nothing_value_as(value, field) {
+                removable.push(key.get().to_owned());
+            }
+        }
+    }
+    table.retain(|key, _| !removable.iter().any(|r| r == key));
+}
+
+/// Returns shape of the struct field or map value for the key
+/// and whether the key can be omitted.
+/// Map entries are pruned, but never removed.
+fn field_shape<'a>(shape: &'a Shape, key: &str) -> Option<(&'a Shape, bool)> {
+    match &inner(shape).kind {
+        Kind::Struct { fields, .. } => fields
+            .iter()
+            .find(|f| f.name == key)
+            .map(|f| (&f.shape, f.omittable)),
+        Kind::Map { value, .. } => Some((value, false)),
+        _ => None,
+    }
+}
+
+/// Present optional value is pruned as the value itself.
+fn unwrap_option(shape: &Shape) -> &Shape {
+    match &inner(shape).kind {
+        Kind::Option(shape) => inner(shape),
+        _ => inner(shape),
+    }
+}
+
+fn prune_item_as(item: &mut Item, shape: &Shape) {
+    let shape = unwrap_option(shape);
+
+    match item {
+        Item::Value(value) => prune_value_as(value, shape),
+        Item::Table(table) => prune_table_as(table, shape),
+        Item::ArrayOfTables(tables) => {
+            if let Kind::Seq(element) = &shape.kind {
+                tables.iter_mut().for_each(|t| prune_table_as(t, element));
+            }
+        }
+        Item::None => {}
+    }
+}
+
+fn prune_value_as(value: &mut Value, shape: &Shape) {
+    let shape = unwrap_option(shape);
+
+    match value {
+        Value::InlineTable(table) => prune_inline_table_as(table, shape),
+        Value::Array(array) => prune_array_as(array, shape),
+        _ => {}
+    }
+}
+
+fn prune_array_as(array: &mut Array, shape: &Shape) {
+    match &shape.kind {
+        Kind::Seq(element) => array.iter_mut().for_each(|v| prune_value_as(v, element)),
+        Kind::Tuple(elements) => array
+            .iter_mut()
+            .zip(elements)
+            .for_each(|(v, element)| prune_value_as(v, element)),
+        _ => {}
+    }
+}
+
+fn is_nothing_item_as(item: &Item, shape: &Shape) -> bool {
+    match item {
+        Item::None => true,
+        Item::Value(value) => is_nothing_value_as(value, shape),
+        Item::Table(table) => table.is_empty() && is_table_kind(shape),
+        Item::ArrayOfTables(tables) => tables.is_empty(),
+    }
+}
+
+fn is_table_kind(shape: &Shape) -> bool {
+    matches!(inner(shape).kind, Kind::Struct { .. } | Kind::Map { .. })
+}
+
+/// Checks whether the value is nothing when deserialized as the shape.
+fn is_nothing_value_as(value: &Value, shape: &Shape) -> bool {
+    let shape = inner(shape);
+    match (&shape.kind, value) {
+        // Present optional value is `Some`, which is never nothing.
+        (Kind::Option(_), _) => false,
+        (Kind::Bool, Value::Boolean(v)) => !*v.value(),
+        (
+            Kind::I8
+            | Kind::I16
+            | Kind::I32
+            | Kind::I64
+            | Kind::I128
+            | Kind::U8
+            | Kind::U16
+            | Kind::U32
+            | Kind::U64
+            | Kind::U128,
+            Value::Integer(v),
+        ) => *v.value() == 0,
+        (Kind::F32 | Kind::F64, Value::Float(v)) => *v.value() == 0.0,
+        (Kind::F32 | Kind::F64, Value::Integer(v)) => *v.value() == 0,
+        (Kind::Str | Kind::Bytes, Value::String(v)) => v.value().is_empty(),
+        (Kind::Char, Value::String(v)) => v.value() == "\0",
+        (Kind::Seq(_) | Kind::Bytes, Value::Array(v)) => v.is_empty(),
+        (Kind::Tuple(elements), Value::Array(v)) => {
+            v.len() == elements.len()
+                && v.iter()
+                    .zip(elements)
+                    .all(|(v, element)| is_nothing_value_as(v, element))
+        }
+        (Kind::Struct { .. } | Kind::Map { .. }, Value::InlineTable(v)) => v.is_empty(),
+        (Kind::Enum { variants, .. }, Value::String(v)) => variants.iter().any(|variant| {
+            variant.name == v.value() && variant.nothing && matches!(variant.payload, Payload::Unit)
+        }),
+        _ => false,
+    }
+}
+
+#[test]
+fn test_prune_nothing() {
+    let mut doc: ::toml_edit::DocumentMut = r#"
+# Top comment.
+name = "web"   # trailing comment
+port = 0
+debug = false
+ratio = 0.0
+tags = []
+levels = [0, 1]
+point = { x = 0, y = 0 }
+
+[limits]
+# Kept because `max` is not nothing.
+max = 10
+min = 0
+
+[empty]
+a = ""
+b = {}
+
+[[servers]]
+host = ""
+"#
+    .parse()
+    .unwrap();
+
+    prune_nothing(&mut doc);
+    assert_eq!(
+        doc.to_string(),
+        r#"
+# Top comment.
+name = "web"   # trailing comment
+levels = [0, 1]
+
+[limits]
+# Kept because `max` is not nothing.
+max = 10
+
+[[servers]]
+"#
+    );
+}
+
+#[test]
+fn test_prune_nothing_for() {
+    use std::{collections::BTreeMap, string::String};
+
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    enum Mode {
+        Off,
+        On,
+    }
+
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct Limits {
+        #[serde(default)]
+        min: u32,
+        max: core::num::NonZeroU32,
+        // Required, so nothing value is kept.
+        step: u32,
+    }
+
+    #[derive(serde_derive::Deserialize)]
+    #[allow(dead_code)]
+    struct Config {
+        #[serde(default)]
+        name: String,
+        #[serde(default)]
+        mode: Option<Mode>,
+        #[serde(default = "crate::FromNothingExt::nothing")]
+        kind: Mode,
+        #[serde(default)]
+        counters: BTreeMap<String, u32>,
+        limits: Limits,
+        #[serde(default)]
+        point: (u32, u32),
+        label: String,
+    }
+
+    let mut doc: ::toml_edit::DocumentMut = r#"
+name = ""
+mode = "Off"
+kind = "Off"
+point = [0, 0]
+label = ""
+unknown = 0
+
+[counters]
+a = 0
+
+[limits]
+min = 0  # removed
+max = 1
+step = 0
+"#
+    .parse()
+    .unwrap();
+
+    prune_nothing_for::<Config>(&mut doc);
+    assert_eq!(
+        doc.to_string(),
+        r#"mode = "Off"
+label = ""
+unknown = 0
+
+[counters]
+a = 0
+
+[limits]
+max = 1
+step = 0
+"#
+    );
+}
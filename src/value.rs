@@ -0,0 +1,1254 @@
+//!
+//! Tree of values in terms of `serde` data model.
+//!
+//! [`Value`] is filled by serializing any `Serialize` value with [`to_value`]
+//! or by deserializing it from self-describing format
+//! and is read back with [`from_value`] or its `IntoDeserializer` implementation.
+//! It is common ground for inspecting, pruning, merging and comparing values
+//! of types that are only known to be `Serialize`.
+//!
+//! Requires `alloc` feature.
+//!
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{convert::TryFrom, fmt, marker::PhantomData};
+
+use serde::{
+    de::{
+        value::{MapDeserializer, SeqDeserializer},
+        DeserializeOwned, DeserializeSeed, EnumAccess, Error, IntoDeserializer, MapAccess,
+        SeqAccess, Unexpected, VariantAccess, Visitor,
+    },
+    forward_to_deserialize_any, ser,
+    ser::{SerializeMap as _, SerializeSeq as _, SerializeTuple as _},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{protocol, Nothing};
+
+/// Any value in `serde` data model.
+///
+/// Type names are not kept, struct fields and variant names are.
+/// Newtype structs that declare nothingness with `protocol` names
+/// are kept as `Declared`.
+/// Values deserialized from formats never contain `Tuple`, `Struct` and `Variant`,
+/// since formats do not tell tuples from sequences, structs from maps
+/// or enums from strings and maps.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// Boolean value.
+    Bool(bool),
+
+    /// Signed integer.
+    I64(i64),
+
+    /// Unsigned integer.
+    U64(u64),
+
+    /// Signed integer out of `i64` range.
+    I128(i128),
+
+    /// Unsigned integer out of `u64` range.
+    U128(u128),
+
+    /// Floating point number.
+    F64(f64),
+
+    /// Character.
+    Char(char),
+
+    /// String.
+    String(String),
+
+    /// Byte array.
+    Bytes(Vec<u8>),
+
+    /// Unit, unit struct or `null`.
+    Unit,
+
+    /// `None` option.
+    None,
+
+    /// `Some` option.
+    Some(Box<Value>),
+
+    /// Newtype struct.
+    Newtype(Box<Value>),
+
+    /// Newtype struct with one of reserved names from `protocol` module,
+    /// `true` if it declares the value to be nothing.
+    /// Serialized with the same name, so the declaration is not lost.
+    Declared(bool, Box<Value>),
+
+    /// Sequence.
+    Seq(Vec<Value>),
+
+    /// Tuple or tuple struct.
+    Tuple(Vec<Value>),
+
+    /// Map entries in order.
+    Map(Vec<(Value, Value)>),
+
+    /// Struct fields in order.
+    Struct(Vec<(String, Value)>),
+
+    /// Enum variant with payload.
+    /// Unit variants have `Unit` payload,
+    /// tuple variants have `Tuple` payload
+    /// and struct variants have `Struct` payload.
+    Variant(String, Box<Value>),
+}
+
+impl Value {
+    fn unexpected(&self) -> Unexpected<'_> {
+        match self {
+            Value::Bool(v) => Unexpected::Bool(*v),
+            Value::I64(v) => Unexpected::Signed(*v),
+            Value::U64(v) => Unexpected::Unsigned(*v),
+            Value::I128(_) | Value::U128(_) => Unexpected::Other("128-bit integer"),
+            Value::F64(v) => Unexpected::Float(*v),
+            Value::Char(v) => Unexpected::Char(*v),
+            Value::String(v) => Unexpected::Str(v),
+            Value::Bytes(v) => Unexpected::Bytes(v),
+            Value::Unit => Unexpected::Unit,
+            Value::None | Value::Some(_) => Unexpected::Option,
+            Value::Newtype(_) | Value::Declared(..) => Unexpected::NewtypeStruct,
+            Value::Seq(_) | Value::Tuple(_) => Unexpected::Seq,
+            Value::Map(_) | Value::Struct(_) => Unexpected::Map,
+            Value::Variant(..) => Unexpected::Enum,
+        }
+    }
+
+    /// Returns true if the value is nothing.
+    ///
+    /// Same rules as for `Nothing` serializer, except registry rules,
+    /// since type names are not kept.
+    /// Tuples, structs and variants are nothing if all their parts are nothing,
+    /// sequences and maps only if they are empty.
+    pub fn is_nothing(&self) -> bool {
+        match self {
+            Value::Bool(v) => !*v,
+            Value::I64(v) => *v == 0,
+            Value::U64(v) => *v == 0,
+            Value::I128(v) => *v == 0,
+            Value::U128(v) => *v == 0,
+            Value::F64(v) => *v == 0.0,
+            Value::Char(v) => *v == '\0',
+            Value::String(v) => v.is_empty(),
+            Value::Bytes(v) => v.is_empty(),
+            Value::Unit | Value::None => true,
+            Value::Some(_) => false,
+            Value::Newtype(v) => v.is_nothing(),
+            Value::Declared(nothing, _) => *nothing,
+            Value::Seq(v) => v.is_empty(),
+            Value::Tuple(v) => v.iter().all(Value::is_nothing),
+            Value::Map(v) => v.is_empty(),
+            Value::Struct(v) => v.iter().all(|(_, v)| v.is_nothing()),
+            Value::Variant(_, v) => v.is_nothing(),
+        }
+    }
+
+    /// Removes every nothing subtree.
+    ///
+    /// Struct fields and map entries with nothing values are removed,
+    /// bottom up, so structs and maps left without entries are removed too.
+    /// Sequence and tuple elements are pruned but never removed,
+    /// since that would shift positions of other elements.
+    ///
+    /// Pruned struct fields are filled back from `Nothing` when deserialized.
+    /// Content of `Declared` values is kept as is.
+    ///
+    /// ```
+    /// # use serde_nothing::value::{to_value, Value, ValueError};
+    /// #[derive(serde_derive::Serialize)]
+    /// struct Limits { min: u32, max: Option<u32> }
+    ///
+    /// #[derive(serde_derive::Serialize)]
+    /// struct Config { name: String, limits: Limits, tags: Vec<String> }
+    ///
+    /// let config = Config { name: "a".into(), limits: Limits { min: 0, max: None }, tags: vec![] };
+    /// let mut value = to_value::<_, ValueError>(&config).unwrap();
+    /// value.prune();
+    /// assert_eq!(value, Value::Struct(vec![("name".into(), Value::String("a".into()))]));
+    /// ```
+    pub fn prune(&mut self) {
+        match self {
+            Value::Some(v) | Value::Newtype(v) | Value::Variant(_, v) => v.prune(),
+            Value::Seq(v) | Value::Tuple(v) => v.iter_mut().for_each(Value::prune),
+            Value::Map(v) => {
+                v.iter_mut().for_each(|(_, v)| v.prune());
+                v.retain(|(_, v)| !v.is_nothing());
+            }
+            Value::Struct(v) => {
+                v.iter_mut().for_each(|(_, v)| v.prune());
+                v.retain(|(_, v)| !v.is_nothing());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Error of serialization into and deserialization from `Value`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValueError(String);
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValueError {}
+
+impl ser::Error for ValueError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        ValueError(msg.to_string())
+    }
+}
+
+impl Error for ValueError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        ValueError(msg.to_string())
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Value::Bool(v) => serializer.serialize_bool(*v),
+            Value::I64(v) => serializer.serialize_i64(*v),
+            Value::U64(v) => serializer.serialize_u64(*v),
+            Value::I128(v) => serializer.serialize_i128(*v),
+            Value::U128(v) => serializer.serialize_u128(*v),
+            Value::F64(v) => serializer.serialize_f64(*v),
+            Value::Char(v) => serializer.serialize_char(*v),
+            Value::String(v) => serializer.serialize_str(v),
+            Value::Bytes(v) => serializer.serialize_bytes(v),
+            Value::Unit => serializer.serialize_unit(),
+            Value::None => serializer.serialize_none(),
+            Value::Some(v) => serializer.serialize_some(&**v),
+            Value::Newtype(v) => v.serialize(serializer),
+            Value::Declared(nothing, v) => protocol::serialize_declared(serializer, *nothing, v),
+            Value::Seq(v) => {
+                let mut seq = serializer.serialize_seq(Some(v.len()))?;
+                for element in v {
+                    seq.serialize_element(element)?;
+                }
+                seq.end()
+            }
+            Value::Tuple(v) => {
+                let mut tuple = serializer.serialize_tuple(v.len())?;
+                for element in v {
+                    tuple.serialize_element(element)?;
+                }
+                tuple.end()
+            }
+            Value::Map(v) => {
+                let mut map = serializer.serialize_map(Some(v.len()))?;
+                for (key, value) in v {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Value::Struct(v) => {
+                let mut map = serializer.serialize_map(Some(v.len()))?;
+                for (key, value) in v {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            // Externally tagged, same as `Deserialize` implementation expects.
+            Value::Variant(name, payload) => match &**payload {
+                Value::Unit => serializer.serialize_str(name),
+                payload => {
+                    let mut map = serializer.serialize_map(Some(1))?;
+                    map.serialize_entry(name, payload)?;
+                    map.end()
+                }
+            },
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::I64(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::U64(v))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Value, E> {
+        Ok(int128(v))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Value, E> {
+        Ok(uint128(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::F64(v))
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Value, E> {
+        Ok(Value::Char(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.into()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Value, E> {
+        Ok(Value::Bytes(v.into()))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Value, E> {
+        Ok(Value::Bytes(v))
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Value::deserialize(deserializer).map(|v| Value::Some(Box::new(v)))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Unit)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Value::deserialize(deserializer).map(|v| Value::Newtype(Box::new(v)))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(element) = seq.next_element()? {
+            elements.push(element);
+        }
+        Ok(Value::Seq(elements))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Value::Map(entries))
+    }
+}
+
+/// Deserializer from owned `Value`.
+///
+/// Struct fields missing from the value are deserialized from `Nothing`,
+/// so pruned values are read back.
+/// This applies to every missing field, not only to pruned ones:
+/// a field that is missing by mistake is silently filled with nothing value
+/// instead of failing with "missing field" error.
+/// Only fields that cannot be nothing, like `NonZeroU32`, are still reported as missing.
+pub struct ValueDeserializer<E> {
+    value: Value,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E> IntoDeserializer<'de, E> for Value
+where
+    E: Error,
+{
+    type Deserializer = ValueDeserializer<E>;
+
+    fn into_deserializer(self) -> ValueDeserializer<E> {
+        ValueDeserializer {
+            value: self,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, E> Deserializer<'de> for ValueDeserializer<E>
+where
+    E: Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::I64(v) => visitor.visit_i64(v),
+            Value::U64(v) => visitor.visit_u64(v),
+            Value::I128(v) => visitor.visit_i128(v),
+            Value::U128(v) => visitor.visit_u128(v),
+            Value::F64(v) => visitor.visit_f64(v),
+            Value::Char(v) => visitor.visit_char(v),
+            Value::String(v) => visitor.visit_string(v),
+            Value::Bytes(v) => visitor.visit_byte_buf(v),
+            Value::Unit => visitor.visit_unit(),
+            Value::None => visitor.visit_none(),
+            Value::Some(v) => visitor.visit_some(v.into_deserializer()),
+            Value::Newtype(v) | Value::Declared(_, v) => {
+                visitor.visit_newtype_struct(v.into_deserializer())
+            }
+            Value::Seq(v) | Value::Tuple(v) => {
+                let mut seq = SeqDeserializer::new(v.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Value::Map(v) => {
+                let mut map = MapDeserializer::new(v.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            Value::Struct(v) => {
+                let mut map = MapDeserializer::new(v.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            Value::Variant(name, payload) => visitor.visit_enum(ValueEnum {
+                variant: Value::String(name),
+                payload: Some(*payload),
+                marker: PhantomData,
+            }),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::None | Value::Unit => visitor.visit_none(),
+            Value::Some(v) => visitor.visit_some(v.into_deserializer()),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _: &'static str, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::Newtype(v) | Value::Declared(_, v) => {
+                visitor.visit_newtype_struct(v.into_deserializer())
+            }
+            _ => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Value::String(_) => visitor.visit_enum(ValueEnum {
+                variant: self.value,
+                payload: None,
+                marker: PhantomData,
+            }),
+            Value::Map(mut entries) if entries.len() == 1 => {
+                let (variant, payload) = entries.pop().unwrap();
+                visitor.visit_enum(ValueEnum {
+                    variant,
+                    payload: Some(payload),
+                    marker: PhantomData,
+                })
+            }
+            Value::Variant(..) => self.deserialize_any(visitor),
+            other => Err(E::invalid_type(other.unexpected(), &"enum")),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        let entries = match self.value {
+            Value::Struct(v) => v
+                .into_iter()
+                .map(|(key, value)| (Value::String(key), value))
+                .collect(),
+            Value::Map(v) => v,
+            _ => return self.deserialize_any(visitor),
+        };
+
+        let mut access = StructAccess {
+            seen: Vec::new(),
+            entries: entries.into_iter(),
+            fields,
+            missing: 0,
+            next: None,
+            marker: PhantomData,
+        };
+        access.seen.resize(fields.len(), false);
+        visitor.visit_map(access)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map identifier ignored_any
+    }
+}
+
+/// Struct fields followed by missing fields deserialized from `Nothing`.
+struct StructAccess<E> {
+    entries: alloc::vec::IntoIter<(Value, Value)>,
+    fields: &'static [&'static str],
+    seen: Vec<bool>,
+    missing: usize,
+    next: Option<Result<Value, &'static str>>,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E> MapAccess<'de> for StructAccess<E>
+where
+    E: Error,
+{
+    type Error = E;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, E>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if let Some((key, value)) = self.entries.next() {
+            if let Value::String(key) = &key {
+                if let Some(idx) = self.fields.iter().position(|f| f == key) {
+                    self.seen[idx] = true;
+                }
+            }
+            self.next = Some(Ok(value));
+            return seed.deserialize(key.into_deserializer()).map(Some);
+        }
+
+        while self.missing < self.fields.len() {
+            let idx = self.missing;
+            self.missing += 1;
+            if !self.seen[idx] {
+                let field = self.fields[idx];
+                self.next = Some(Err(field));
+                return seed.deserialize(field.into_deserializer()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, E>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.next.take() {
+            None => Err(E::custom("value requested before key")),
+            Some(Ok(value)) => seed.deserialize(value.into_deserializer()),
+            Some(Err(field)) => seed
+                .deserialize(Nothing)
+                .map_err(|_| E::missing_field(field)),
+        }
+    }
+}
+
+/// Enum represented as variant name or single-entry map.
+struct ValueEnum<E> {
+    variant: Value,
+    payload: Option<Value>,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E> EnumAccess<'de> for ValueEnum<E>
+where
+    E: Error,
+{
+    type Error = E;
+    type Variant = Self;
+
+    fn variant_seed<V>(mut self, seed: V) -> Result<(V::Value, Self), E>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = core::mem::replace(&mut self.variant, Value::Unit);
+        let value = seed.deserialize(variant.into_deserializer())?;
+        Ok((value, self))
+    }
+}
+
+impl<'de, E> VariantAccess<'de> for ValueEnum<E>
+where
+    E: Error,
+{
+    type Error = E;
+
+    fn unit_variant(self) -> Result<(), E> {
+        match self.payload {
+            None | Some(Value::Unit) => Ok(()),
+            Some(other) => Err(E::invalid_type(other.unexpected(), &"unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, E>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.payload {
+            None => Err(E::invalid_type(Unexpected::UnitVariant, &"newtype variant")),
+            Some(payload) => seed.deserialize(payload.into_deserializer()),
+        }
+    }
+
+    fn tuple_variant<V>(self, _: usize, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.payload {
+            None => Err(E::invalid_type(Unexpected::UnitVariant, &"tuple variant")),
+            Some(payload) => payload.into_deserializer().deserialize_any(visitor),
+        }
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        match self.payload {
+            None => Err(E::invalid_type(Unexpected::UnitVariant, &"struct variant")),
+            Some(payload) => payload
+                .into_deserializer()
+                .deserialize_struct("", fields, visitor),
+        }
+    }
+}
+
+/// Serializes the value into `Value`.
+#[inline]
+pub fn to_value<T, E>(value: &T) -> Result<Value, E>
+where
+    T: ?Sized + Serialize,
+    E: ser::Error,
+{
+    value.serialize(ValueSerializer::new())
+}
+
+/// Deserializes the value from `Value`.
+///
+/// Missing struct fields are filled from `Nothing`, see [`ValueDeserializer`].
+#[inline]
+pub fn from_value<T, E>(value: Value) -> Result<T, E>
+where
+    T: DeserializeOwned,
+    E: Error,
+{
+    T::deserialize(value.into_deserializer())
+}
+
+/// Serializer into `Value`.
+pub struct ValueSerializer<E = ValueError>(PhantomData<E>);
+
+impl<E> ValueSerializer<E> {
+    /// Returns new serializer.
+    #[inline]
+    pub fn new() -> Self {
+        ValueSerializer(PhantomData)
+    }
+}
+
+impl<E> Default for ValueSerializer<E> {
+    #[inline]
+    fn default() -> Self {
+        ValueSerializer::new()
+    }
+}
+
+impl<E> fmt::Debug for ValueSerializer<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ValueSerializer")
+    }
+}
+
+impl<E> Serializer for ValueSerializer<E>
+where
+    E: ser::Error,
+{
+    type Ok = Value;
+    type Error = E;
+    type SerializeSeq = SerializeSeq<E>;
+    type SerializeTuple = SerializeSeq<E>;
+    type SerializeTupleStruct = SerializeSeq<E>;
+    type SerializeTupleVariant = SerializeSeq<E>;
+    type SerializeMap = SerializeMap<E>;
+    type SerializeStruct = SerializeStruct<E>;
+    type SerializeStructVariant = SerializeStruct<E>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, E> {
+        Ok(Value::I64(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, E> {
+        Ok(Value::I64(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, E> {
+        Ok(Value::I64(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, E> {
+        Ok(Value::I64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, E> {
+        Ok(int128(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, E> {
+        Ok(Value::U64(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, E> {
+        Ok(Value::U64(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, E> {
+        Ok(Value::U64(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, E> {
+        Ok(Value::U64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, E> {
+        Ok(uint128(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, E> {
+        Ok(Value::F64(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, E> {
+        Ok(Value::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, E> {
+        Ok(Value::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.into()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, E> {
+        Ok(Value::Bytes(v.into()))
+    }
+
+    fn serialize_none(self) -> Result<Value, E> {
+        Ok(Value::None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value, E>
+    where
+        T: ?Sized + Serialize,
+    {
+        to_value(value).map(|v| Value::Some(Box::new(v)))
+    }
+
+    fn serialize_unit(self) -> Result<Value, E> {
+        Ok(Value::Unit)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Value, E> {
+        Ok(Value::Unit)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Value, E> {
+        Ok(Value::Variant(variant.into(), Box::new(Value::Unit)))
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Value, E>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = Box::new(to_value(value)?);
+        Ok(match name {
+            protocol::NOTHING => Value::Declared(true, value),
+            protocol::SOMETHING => Value::Declared(false, value),
+            _ => Value::Newtype(value),
+        })
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, E>
+    where
+        T: ?Sized + Serialize,
+    {
+        to_value(value).map(|v| Value::Variant(variant.into(), Box::new(v)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeSeq<E>, E> {
+        Ok(SerializeSeq {
+            tuple: false,
+            variant: None,
+            elements: Vec::with_capacity(len.unwrap_or(0).min(4096)),
+            marker: PhantomData,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeSeq<E>, E> {
+        Ok(SerializeSeq {
+            tuple: true,
+            variant: None,
+            elements: Vec::with_capacity(len.min(4096)),
+            marker: PhantomData,
+        })
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, len: usize) -> Result<SerializeSeq<E>, E> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeSeq<E>, E> {
+        Ok(SerializeSeq {
+            tuple: true,
+            variant: Some(variant),
+            elements: Vec::with_capacity(len.min(4096)),
+            marker: PhantomData,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap<E>, E> {
+        Ok(SerializeMap {
+            entries: Vec::with_capacity(len.unwrap_or(0).min(4096)),
+            key: None,
+            marker: PhantomData,
+        })
+    }
+
+    fn serialize_struct(self, _: &'static str, len: usize) -> Result<SerializeStruct<E>, E> {
+        Ok(SerializeStruct {
+            variant: None,
+            fields: Vec::with_capacity(len.min(4096)),
+            marker: PhantomData,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeStruct<E>, E> {
+        Ok(SerializeStruct {
+            variant: Some(variant),
+            fields: Vec::with_capacity(len.min(4096)),
+            marker: PhantomData,
+        })
+    }
+}
+
+/// Keeps integers that fit into 64 bits as `I64`,
+/// same as formats deserialize them.
+fn int128(v: i128) -> Value {
+    match i64::try_from(v) {
+        Ok(v) => Value::I64(v),
+        Err(_) => Value::I128(v),
+    }
+}
+
+/// Keeps integers that fit into 64 bits as `U64`,
+/// same as formats deserialize them.
+fn uint128(v: u128) -> Value {
+    match u64::try_from(v) {
+        Ok(v) => Value::U64(v),
+        Err(_) => Value::U128(v),
+    }
+}
+
+/// Wraps payload into variant if there is one.
+fn variant(variant: Option<&'static str>, payload: Value) -> Value {
+    match variant {
+        None => payload,
+        Some(name) => Value::Variant(name.into(), Box::new(payload)),
+    }
+}
+
+/// Serializer of sequences, tuples and tuple variants into `Value`.
+pub struct SerializeSeq<E> {
+    tuple: bool,
+    variant: Option<&'static str>,
+    elements: Vec<Value>,
+    marker: PhantomData<E>,
+}
+
+impl<E> SerializeSeq<E>
+where
+    E: ser::Error,
+{
+    fn push<T>(&mut self, value: &T) -> Result<(), E>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.elements.push(to_value(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value, E> {
+        let payload = match self.tuple {
+            false => Value::Seq(self.elements),
+            true => Value::Tuple(self.elements),
+        };
+        Ok(variant(self.variant, payload))
+    }
+}
+
+impl<E> ser::SerializeSeq for SerializeSeq<E>
+where
+    E: ser::Error,
+{
+    type Ok = Value;
+    type Error = E;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), E>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, E> {
+        self.finish()
+    }
+}
+
+impl<E> ser::SerializeTuple for SerializeSeq<E>
+where
+    E: ser::Error,
+{
+    type Ok = Value;
+    type Error = E;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), E>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, E> {
+        self.finish()
+    }
+}
+
+impl<E> ser::SerializeTupleStruct for SerializeSeq<E>
+where
+    E: ser::Error,
+{
+    type Ok = Value;
+    type Error = E;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), E>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, E> {
+        self.finish()
+    }
+}
+
+impl<E> ser::SerializeTupleVariant for SerializeSeq<E>
+where
+    E: ser::Error,
+{
+    type Ok = Value;
+    type Error = E;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), E>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, E> {
+        self.finish()
+    }
+}
+
+/// Serializer of maps into `Value`.
+pub struct SerializeMap<E> {
+    entries: Vec<(Value, Value)>,
+    key: Option<Value>,
+    marker: PhantomData<E>,
+}
+
+impl<E> ser::SerializeMap for SerializeMap<E>
+where
+    E: ser::Error,
+{
+    type Ok = Value;
+    type Error = E;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), E>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.key = Some(to_value(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), E>
+    where
+        T: ?Sized + Serialize,
+    {
+        match self.key.take() {
+            None => Err(E::custom("map value serialized before key")),
+            Some(key) => {
+                self.entries.push((key, to_value(value)?));
+                Ok(())
+            }
+        }
+    }
+
+    fn end(self) -> Result<Value, E> {
+        Ok(Value::Map(self.entries))
+    }
+}
+
+/// Serializer of structs and struct variants into `Value`.
+pub struct SerializeStruct<E> {
+    variant: Option<&'static str>,
+    fields: Vec<(String, Value)>,
+    marker: PhantomData<E>,
+}
+
+impl<E> ser::SerializeStruct for SerializeStruct<E>
+where
+    E: ser::Error,
+{
+    type Ok = Value;
+    type Error = E;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), E>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.fields.push((key.into(), to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, E> {
+        Ok(variant(self.variant, Value::Struct(self.fields)))
+    }
+}
+
+impl<E> ser::SerializeStructVariant for SerializeStruct<E>
+where
+    E: ser::Error,
+{
+    type Ok = Value;
+    type Error = E;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), E>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.fields.push((key.into(), to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, E> {
+        Ok(variant(self.variant, Value::Struct(self.fields)))
+    }
+}
+
+#[test]
+fn test_value() {
+    use alloc::{collections::BTreeMap, vec};
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    enum Mode {
+        Off,
+        Fixed(u32),
+        Range { min: u32, max: u32 },
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    struct Config {
+        name: String,
+        port: u16,
+        point: (i32, f32),
+        tags: Vec<String>,
+        limits: BTreeMap<String, u32>,
+        parent: Option<Box<Config>>,
+        modes: Vec<Mode>,
+    }
+
+    let config = Config {
+        name: "a".into(),
+        port: 0,
+        point: (-1, 0.0),
+        tags: vec![],
+        limits: BTreeMap::from([("cpu".into(), 0), ("mem".into(), 2)]),
+        parent: None,
+        modes: vec![Mode::Off, Mode::Fixed(0), Mode::Range { min: 1, max: 0 }],
+    };
+
+    let mut value = to_value::<_, ValueError>(&config).unwrap();
+    assert!(!value.is_nothing());
+    assert_eq!(
+        from_value::<Config, ValueError>(value.clone()).unwrap(),
+        config
+    );
+
+    value.prune();
+    assert_eq!(
+        serde_json::to_string(&value).unwrap(),
+        r#"{"name":"a","point":[-1,0.0],"limits":{"mem":2},"modes":["Off",{"Fixed":0},{"Range":{"min":1}}]}"#
+    );
+
+    // Pruned parts are filled back from nothing, except map entries.
+    let pruned = from_value::<Config, ValueError>(value).unwrap();
+    assert_eq!(pruned.limits.len(), 1);
+    assert_eq!(
+        Config {
+            limits: config.limits.clone(),
+            ..pruned
+        },
+        config
+    );
+
+    #[derive(Debug, serde_derive::Deserialize)]
+    struct Required {
+        #[allow(dead_code)]
+        port: core::num::NonZeroU16,
+    }
+
+    let err = from_value::<Required, ValueError>(Value::Struct(vec![])).unwrap_err();
+    assert_eq!(err.to_string(), "missing field `port`");
+
+    assert!(Value::Struct(vec![("a".into(), Value::U64(0))]).is_nothing());
+    assert!(Value::Tuple(vec![Value::U64(0), Value::None]).is_nothing());
+    assert!(!Value::Seq(vec![Value::U64(0)]).is_nothing());
+    assert!(!Value::Map(vec![(Value::Unit, Value::Unit)]).is_nothing());
+    assert!(!Value::Some(Box::new(Value::Unit)).is_nothing());
+
+    let value = to_value::<_, ValueError>(&(u128::MAX, i128::MIN, 1u128)).unwrap();
+    assert_eq!(
+        value,
+        Value::Tuple(vec![
+            Value::U128(u128::MAX),
+            Value::I128(i128::MIN),
+            Value::U64(1)
+        ])
+    );
+    assert_eq!(
+        from_value::<(u128, i128, u128), ValueError>(value),
+        Ok((u128::MAX, i128::MIN, 1))
+    );
+
+    struct Declared(bool, u32);
+
+    impl Serialize for Declared {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            protocol::serialize_declared(serializer, self.0, &self.1)
+        }
+    }
+
+    // Declaration wins over content, same as with `Nothing` serializer.
+    for declared in [Declared(true, 5), Declared(false, 0)] {
+        let mut value = to_value::<_, ValueError>(&declared).unwrap();
+        assert_eq!(value.is_nothing(), crate::is_nothing(&declared));
+        assert_eq!(value.serialize(crate::Nothing).is_ok(), declared.0);
+        value.prune();
+        assert_eq!(
+            value,
+            Value::Declared(declared.0, Box::new(Value::U64(declared.1.into())))
+        );
+    }
+}